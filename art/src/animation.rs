@@ -0,0 +1,204 @@
+/* Progressive drawing of an artwork's strokes.
+
+   A frame is due every `FRAME_MS` of uptime and draws a fixed number of samples, so curves
+   unroll at the same pace on every chip no matter how fast its SPI is. Frames which couldn't
+   be drawn in time are dropped rather than caught up in a burst. */
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+use libm::sqrtf;
+
+use crate::artworks::{Drawing, ASPECT};
+use crate::curve::Samples;
+use crate::plotter::Plotter;
+use crate::polyline::{Pen, Polyline};
+use crate::viewport::{Pixels, Viewport};
+
+pub const FRAME_MS: u64 = 20;
+
+/* Samples per frame, 40 draws the Eye in about 7 s */
+pub const DEFAULT_RATE: u32 = 40;
+const MIN_RATE: u32 = 5;
+const MAX_RATE: u32 = 2560;
+
+/* Only one of them is needed, glyphs or lines don't change while animating. Banded builds have
+   a smaller plotter, which is fine to keep in a polyline's room */
+#[allow(clippy::large_enum_variant)]
+enum Tool {
+    Plotter(Plotter),
+    Lines(Polyline),
+}
+
+pub struct Animation<'a> {
+    drawing: Drawing<'a>,
+    /* Stroke being drawn */
+    stroke: usize,
+    /* When drawing glyphs: what is left of its samples, made on the first frame as they depend
+       on the size of the target */
+    samples: Option<Pixels<Samples<'a>>>,
+    /* Plotter or polyline, made on the first frame as well and kept for the whole animation */
+    tool: Option<Tool>,
+    /* When drawing lines: its next corner, and where the last one was */
+    corner: u32,
+    vertex: (f32, f32),
+    rate: u32,
+    paused: bool,
+    next_frame: u64,
+}
+
+impl<'a> Animation<'a> {
+    pub fn new(drawing: Drawing<'a>, rate: u32, now: u64) -> Self {
+        Animation {
+            drawing,
+            stroke: 0,
+            samples: None,
+            tool: None,
+            corner: 0,
+            vertex: (0.0, 0.0),
+            rate: rate.clamp(MIN_RATE, MAX_RATE),
+            paused: false,
+            next_frame: now,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.stroke >= self.drawing.strokes.len()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    pub fn faster(&mut self) {
+        self.rate = (self.rate * 2).min(MAX_RATE);
+    }
+
+    pub fn slower(&mut self) {
+        self.rate = (self.rate / 2).max(MIN_RATE);
+    }
+
+    pub fn toggle_pause(&mut self, now: u64) {
+        self.paused = !self.paused;
+        /* Time spent paused doesn't count as missed frames */
+        self.next_frame = now;
+    }
+
+    /* Draws a frame if one is due, returns whether anything was drawn */
+    pub fn tick<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, now: u64) -> Result<bool, D::Error> {
+        if self.paused || self.is_done() || now < self.next_frame {
+            return Ok(false);
+        }
+        self.next_frame = (self.next_frame + FRAME_MS).max(now);
+        self.draw(display, self.rate)?;
+        Ok(true)
+    }
+
+    /* Draws one frame right away, meant for stepping through a paused animation */
+    pub fn step<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D) -> Result<(), D::Error> {
+        self.draw(display, self.rate)
+    }
+
+    fn draw<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, count: u32) -> Result<(), D::Error> {
+        if self.drawing.line_width > 0 {
+            return self.draw_lines(display, count);
+        }
+
+        let strokes = self.drawing.strokes;
+        let Some(first) = strokes.get(self.stroke) else {
+            return Ok(());
+        };
+        let theme = self.drawing.theme;
+        let viewport = Viewport::fit(display.bounding_box(), ASPECT);
+        let tool = self.tool.get_or_insert_with(|| Tool::Plotter(Plotter::new(display.bounding_box(), first.brush(), theme.foreground)));
+        let Tool::Plotter(plotter) = tool else {
+            return Ok(());
+        };
+        let mut left = count;
+        while left > 0 {
+            let Some(stroke) = strokes.get(self.stroke) else {
+                break;
+            };
+            match self.samples.get_or_insert_with(|| stroke.samples(&viewport)).next() {
+                Some((i, pixel)) => {
+                    plotter.set_color(display, stroke.sample_color(i, theme))?;
+                    plotter.plot(display, pixel)?;
+                    left -= 1;
+                }
+                None => {
+                    plotter.flush(display)?;
+                    self.stroke += 1;
+                    self.samples = None;
+                    if let Some(next) = strokes.get(self.stroke) {
+                        plotter.set_brush(next.brush());
+                    }
+                }
+            }
+        }
+        plotter.flush(display)
+    }
+
+    /* Lines unroll at the pace glyphs would: a sample takes up about a pixel of the curve, so
+       a frame draws `count` pixels of it */
+    fn draw_lines<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, count: u32) -> Result<(), D::Error> {
+        let pen = self.drawing.pen();
+        let viewport = Viewport::fit(display.bounding_box(), ASPECT);
+        let tool = self.tool.get_or_insert_with(|| Tool::Lines(Polyline::new(display.bounding_box())));
+        let Tool::Lines(lines) = tool else {
+            return Ok(());
+        };
+        let mut left = count as f32;
+        while left > 0.0 {
+            let Some(stroke) = self.drawing.strokes.get(self.stroke) else {
+                break;
+            };
+            if self.corner >= stroke.corners() {
+                self.stroke += 1;
+                self.corner = 0;
+                continue;
+            }
+            let vertex = stroke.corner(self.corner, &viewport);
+            if self.corner == 0 {
+                lines.restart();
+            } else {
+                let pen = Pen { color: stroke.corner_color(self.corner, self.drawing.theme), ..pen };
+                lines.draw_line(display, self.vertex, vertex, pen)?;
+                let (dx, dy) = (vertex.0 - self.vertex.0, vertex.1 - self.vertex.1);
+                left -= sqrtf(dx * dx + dy * dy);
+            }
+            self.vertex = vertex;
+            self.corner += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Animation;
+    use crate::artworks::{Drawing, HYPOTROCHOID_STROKES};
+    use crate::mock::Screen;
+    use crate::scene::Scene;
+    use crate::theme::THEMES;
+
+    /* Frame by frame, with one plotter throughout, it ends up as the picture drawn at once */
+    #[test]
+    fn frames_add_up_to_the_picture() {
+        let drawing = Drawing { strokes: &HYPOTROCHOID_STROKES, line_width: 0, theme: &THEMES[0] };
+        let mut direct = Screen::new(320, 240, THEMES[0].background);
+        drawing.draw(&mut direct).unwrap();
+
+        let mut animated = Screen::new(320, 240, THEMES[0].background);
+        let mut animation = Animation::new(drawing, 40, 0);
+        let mut frames = 0;
+        while !animation.is_done() {
+            animation.step(&mut animated).unwrap();
+            frames += 1;
+        }
+        assert!(frames > 1);
+        assert!(animated.pixels == direct.pixels);
+        assert!(direct.lit(THEMES[0].background) > 0);
+    }
+}
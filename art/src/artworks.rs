@@ -0,0 +1,376 @@
+/* Artworks, adapted from Samuel Benko's esp32s3-box examples.
+
+   Every artwork is a list of strokes: a curve sampled in even steps of its parameter and
+   stamped with one brush. Keeping them as data rather than loops lets a picture be drawn all
+   at once as well as a few samples at a time. The first three are written as text in
+   `scenes/`, see `scene_file`.
+
+   Points are in the units of `Viewport`, so pictures fill whatever they are drawn into. The
+   curves were designed on a 320x240 screen and keep its numbers, `design` scales them down. */
+
+use embedded_graphics::{
+    mono_font::ascii::FONT_10X20,
+    pixelcolor::Rgb565,
+    prelude::*,
+};
+
+use crate::curve::{Curve, Samples, Spirograph};
+use crate::gradient::Gradient;
+use crate::lsystem::{Growth, LSystem, Path, Variation};
+use crate::plotter::{Brush, Plotter};
+use crate::polyline::{Pen, Polyline};
+use crate::scene::Scene;
+use crate::theme::Theme;
+use crate::viewport::{Pixels, Viewport};
+
+/* Most strokes an artwork has */
+pub const MAX_STROKES: usize = 32;
+
+#[derive(Clone, Copy)]
+pub struct Stroke<'a> {
+    pub tip: Tip<'a>,
+    pub curve: &'a dyn Curve,
+    /* Samples per unit of the curve's parameter */
+    pub density: f32,
+    /* Pixels between the samples the brush is stamped on, 0 stamps every one */
+    pub spacing: f32,
+    pub lines: Lines<'a>,
+    pub color: Gradient<'a>,
+}
+
+/* What a stroke's samples are stamped with, see `Brush` */
+#[derive(Clone, Copy)]
+pub enum Tip<'a> {
+    /* FONT_10X20 glyph, as the artworks were originally drawn */
+    Glyph(char),
+    Pixel,
+    Disc(u32),
+    /* One `u32` per row, `width` wide, `hotspot` landing on the sample */
+    Bitmap { rows: &'a [u32], width: u32, hotspot: Point },
+}
+
+/* How a stroke is drawn as a polyline */
+#[derive(Clone, Copy)]
+pub enum Lines<'a> {
+    /* Joins every n-th sample, and the last one */
+    Every(u32),
+    /* Traces another curve instead, with both ends of its range */
+    Outline { curve: &'a dyn Curve, density: f32 },
+}
+
+impl<'a> Stroke<'a> {
+    pub fn brush(&self) -> Brush {
+        match self.tip {
+            Tip::Glyph(glyph) => Brush::glyph(&FONT_10X20, glyph),
+            Tip::Pixel => Brush::pixel(),
+            Tip::Disc(diameter) => Brush::disc(diameter),
+            Tip::Bitmap { rows, width, hotspot } => Brush::bitmap(rows, width, hotspot),
+        }
+    }
+
+    /* Points the brush is stamped on, tagged with their index among all of the samples */
+    pub fn samples(&self, viewport: &Viewport) -> Pixels<Samples<'a>> {
+        let samples = self.curve.sample(self.density);
+        viewport.pixels(samples.spaced(self.spacing / viewport.scale() as f32))
+    }
+
+    pub fn sample_color(&self, i: u32, theme: &Theme) -> Rgb565 {
+        let last = self.curve.sample(self.density).len().saturating_sub(1).max(1);
+        self.color.at(i as f32 / last as f32, theme.foreground)
+    }
+
+    pub fn corner_color(&self, j: u32, theme: &Theme) -> Rgb565 {
+        self.color.at(j as f32 / self.corners().saturating_sub(1).max(1) as f32, theme.foreground)
+    }
+
+    pub fn corners(&self) -> u32 {
+        let length = self.curve.sample(self.density).len();
+        match self.lines {
+            _ if length == 0 => 0,
+            Lines::Every(n) => (length - 1).div_ceil(n.max(1)) + 1,
+            Lines::Outline { curve, density } => curve.sample(density).with_end().len(),
+        }
+    }
+
+    pub fn corner(&self, j: u32, viewport: &Viewport) -> (f32, f32) {
+        viewport.position(match self.lines {
+            Lines::Every(n) => {
+                let samples = self.curve.sample(self.density);
+                samples.point((j * n).min(samples.len() - 1))
+            }
+            Lines::Outline { curve, density } => curve.sample(density).point(j),
+        })
+    }
+}
+
+/* Strokes and how to draw them: glyph brushes when `line_width` is 0, anti-aliased lines otherwise */
+#[derive(Clone, Copy)]
+pub struct Drawing<'a> {
+    pub strokes: &'a [Stroke<'a>],
+    pub line_width: u8,
+    pub theme: &'static Theme,
+}
+
+impl Drawing<'_> {
+    /* Strokes set the colour of each segment themselves */
+    pub fn pen(&self) -> Pen {
+        Pen {
+            width: self.line_width as f32,
+            color: self.theme.foreground,
+            background: self.theme.background,
+        }
+    }
+}
+
+impl Scene for Drawing<'_> {
+    /* Draws the strokes one after another, each is shown as soon as it's complete */
+    fn draw<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D) -> Result<(), D::Error> {
+        let viewport = Viewport::fit(display.bounding_box(), ASPECT);
+        if self.line_width > 0 {
+            let mut lines = Polyline::new(display.bounding_box());
+            for stroke in self.strokes {
+                let corners = (0..stroke.corners()).map(|j| (stroke.corner(j, &viewport), stroke.corner_color(j, self.theme)));
+                lines.draw(display, corners, self.pen())?;
+            }
+            return Ok(());
+        }
+
+        let Some(first) = self.strokes.first() else {
+            return Ok(());
+        };
+        let mut plotter = Plotter::new(display.bounding_box(), first.brush(), self.theme.foreground);
+        for stroke in self.strokes {
+            plotter.flush(display)?;
+            plotter.set_brush(stroke.brush());
+            for (i, pixel) in stroke.samples(&viewport) {
+                plotter.set_color(display, stroke.sample_color(i, self.theme))?;
+                plotter.plot(display, pixel)?;
+            }
+        }
+        plotter.flush(display)
+    }
+}
+
+/* Width of the frame the artworks are laid out in, for a height of 1 */
+pub const ASPECT: f64 = 320.0 / 240.0;
+
+/* One pixel of the screen the artworks were designed on */
+pub const PX: f32 = 1.0 / 240.0;
+
+/* From pixels of the 320x240 design to frame units */
+pub const fn design(x: f32, y: f32) -> (f32, f32) {
+    (x / 240.0, y / 240.0)
+}
+
+const BLUE: Rgb565 = Rgb565::new(3, 20, 27);
+const PURPLE: Rgb565 = Rgb565::new(16, 8, 20);
+pub const STEM: Rgb565 = Rgb565::new(4, 34, 4);
+static SUNSET: [Rgb565; 3] = [Rgb565::new(31, 40, 0), Rgb565::new(29, 10, 4), Rgb565::new(18, 4, 16)];
+
+/* Ring and wheel sizes of the toy, in teeth */
+const HYPOTROCHOID: Spirograph = spirograph(105, 36, 30.0, false);
+const LACE: Spirograph = spirograph(96, 56, 52.0, false);
+const EPITROCHOID: Spirograph = spirograph(60, 21, 30.0, true);
+
+/* A sample per pixel where the pen is fastest, a corner every 4 */
+pub static HYPOTROCHOID_STROKES: [Stroke; 1] = [Stroke {
+    tip: Tip::Glyph('.'),
+    curve: &HYPOTROCHOID,
+    density: HYPOTROCHOID.speed() * HYPOTROCHOID.tooth / PX,
+    spacing: 1.0,
+    lines: Lines::Every(4),
+    color: Gradient::Hue { from: 0, to: 360 },
+}];
+
+pub static LACE_STROKES: [Stroke; 1] = [Stroke {
+    tip: Tip::Glyph('.'),
+    curve: &LACE,
+    density: LACE.speed() * LACE.tooth / PX,
+    spacing: 1.0,
+    lines: Lines::Every(4),
+    color: Gradient::TwoStop(BLUE, PURPLE),
+}];
+
+pub static EPITROCHOID_STROKES: [Stroke; 1] = [Stroke {
+    tip: Tip::Glyph('.'),
+    curve: &EPITROCHOID,
+    density: EPITROCHOID.speed() * EPITROCHOID.tooth / PX,
+    spacing: 1.0,
+    lines: Lines::Every(4),
+    color: Gradient::Palette(&SUNSET),
+}];
+
+/* Centred, the pen reaching 108 px out at most */
+pub const fn spirograph(ring: u32, wheel: u32, pen: f32, outside: bool) -> Spirograph {
+    let spirograph = Spirograph {
+        centre: design(160.0, 120.0),
+        ring,
+        wheel,
+        pen,
+        outside,
+        tooth: 1.0,
+    };
+    Spirograph {
+        tooth: 108.0 * PX / spirograph.reach(),
+        ..spirograph
+    }
+}
+
+/* Room the gallery lends L-systems and the stems of scenes, 16 KB */
+pub const PATH_VERTICES: usize = 4096;
+
+/* Most vertices a `stem` grows into, with none of its leaves pruned */
+pub const STEM_VERTICES: usize = 31;
+
+/* Up from the ground `length` pixels below (x, y) to (x, y) */
+pub const fn stem(x: f32, y: f32, length: f32) -> Growth {
+    Growth {
+        system: LSystem::STEM,
+        root: design(x, y + length),
+        heading: 90.0,
+        step: length / (2 * LSystem::STEM.depth) as f32 * PX,
+        variation: Variation { angle: 0.4, length: 0.4, prune: 0.35 },
+    }
+}
+
+const LEAF: Rgb565 = Rgb565::new(10, 48, 8);
+
+/* A calligraphy pen held at 45 degrees: branches going up to the right are thin, those across them broad */
+static NIB: [u32; 4] = [0b1000, 0b0100, 0b0010, 0b0001];
+
+/* An L-system grown whenever its artwork is opened, and the stroke drawing it, which points at
+   the path once it's grown */
+pub struct Grown {
+    pub growth: Growth,
+    pub stroke: Stroke<'static>,
+}
+
+/* 31.5 steps wide, 32.7 tall. Grows a bit differently every time, its trunk stays put */
+pub static PLANT: Grown = Grown {
+    growth: Growth {
+        system: LSystem::PLANT,
+        root: design(58.0, 228.0),
+        heading: 65.0,
+        step: 6.5 * PX,
+        variation: Variation { angle: 0.25, length: 0.2, prune: 0.1 },
+    },
+    stroke: Stroke {
+        tip: Tip::Bitmap { rows: &NIB, width: 4, hotspot: Point::new(2, 2) },
+        curve: &Path::NONE,
+        density: 7.0,
+        spacing: 1.0,
+        lines: Lines::Every(7),
+        color: Gradient::TwoStop(STEM, LEAF),
+    },
+};
+
+/* 81 steps wide, 93.5 tall */
+pub static KOCH_SNOWFLAKE: Grown = Grown {
+    growth: Growth {
+        system: LSystem::KOCH_SNOWFLAKE,
+        root: design(75.0, 71.0),
+        heading: 0.0,
+        step: 2.1 * PX,
+        variation: Variation::NONE,
+    },
+    stroke: Stroke {
+        tip: Tip::Pixel,
+        curve: &Path::NONE,
+        density: 3.0,
+        spacing: 1.0,
+        lines: Lines::Every(3),
+        color: Gradient::Hue { from: 180, to: 300 },
+    },
+};
+
+/* 31 steps wide, 47 tall */
+pub static DRAGON: Grown = Grown {
+    growth: Growth {
+        system: LSystem::DRAGON,
+        root: design(138.0, 174.0),
+        heading: 0.0,
+        step: 4.0 * PX,
+        variation: Variation::NONE,
+    },
+    stroke: Stroke {
+        tip: Tip::Disc(3),
+        curve: &Path::NONE,
+        density: 4.0,
+        spacing: 1.0,
+        lines: Lines::Every(4),
+        color: Gradient::Palette(&SUNSET),
+    },
+};
+
+/* Turtle scripts, run by `turtle` whenever they're opened */
+pub const FLOWER_SCRIPT: &str = include_str!("scripts/flower.turtle");
+pub const SQUARES_SCRIPT: &str = include_str!("scripts/squares.turtle");
+pub const CRYSTAL_SCRIPT: &str = include_str!("scripts/crystal.turtle");
+
+/* Scenes, loaded by `scene_file` whenever they're opened */
+pub const EYE_SCENE: &str = include_str!("scenes/eye.scene");
+pub const LOLLIPOP_GUY_SCENE: &str = include_str!("scenes/lollipop_guy.scene");
+pub const GARDEN_SCENE: &str = include_str!("scenes/garden.scene");
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{println, vec::Vec};
+
+    use super::*;
+    use crate::animation::{Animation, DEFAULT_RATE};
+    use crate::lsystem::Paths;
+    use crate::mock::Screen;
+    use crate::theme::THEMES;
+    use crate::{scene_file, turtle};
+
+    /* Pixels sent drawing `strokes` on a 320x240 screen at once and frame by frame */
+    fn writes(strokes: &[Stroke]) -> (usize, usize) {
+        let drawing = Drawing { strokes, line_width: 0, theme: &THEMES[0] };
+        let mut direct = Screen::new(320, 240, THEMES[0].background);
+        drawing.draw(&mut direct).unwrap();
+        let mut animated = Screen::new(320, 240, THEMES[0].background);
+        let mut animation = Animation::new(drawing, DEFAULT_RATE, 0);
+        while !animation.is_done() {
+            animation.step(&mut animated).unwrap();
+        }
+        (direct.writes, animated.writes)
+    }
+
+    /* Every artwork drawn with a brush on each sample, as before strokes had a spacing, and as
+       shipped. Prints the counts, see them with `cargo test pixel_writes -- --nocapture` */
+    #[test]
+    fn pixel_writes() {
+        let check = |name: &str, strokes: &[Stroke]| {
+            let every: Vec<Stroke> = strokes.iter().map(|stroke| Stroke { spacing: 0.0, ..*stroke }).collect();
+            let before = writes(&every);
+            let after = writes(strokes);
+            println!("{name:16} {:>7} -> {:<7} {:>7} -> {:<7}", before.0, after.0, before.1, after.1);
+            assert!(after.0 <= before.0, "{name} sends more pixels");
+            /* One plotter for the whole animation doesn't send anything twice in a colour */
+            assert_eq!(after.1, after.0, "{name} sends more pixels animated");
+        };
+        println!("{:16} {:^17} {:^17}", "", "direct", "animated");
+
+        let mut paths: Paths<PATH_VERTICES> = Paths::new();
+        for (name, scene) in [("Eye", EYE_SCENE), ("Lollipop Guy", LOLLIPOP_GUY_SCENE), ("Garden", GARDEN_SCENE)] {
+            let Ok(scene) = scene_file::load(scene, 1, &mut paths) else {
+                panic!("{name} doesn't parse");
+            };
+            check(name, &scene.strokes());
+        }
+        check("Hypotrochoid", &HYPOTROCHOID_STROKES);
+        check("Lace", &LACE_STROKES);
+        check("Epitrochoid", &EPITROCHOID_STROKES);
+        for (name, grown) in [("Fractal plant", &PLANT), ("Koch snowflake", &KOCH_SNOWFLAKE), ("Dragon curve", &DRAGON)] {
+            paths.grow(core::slice::from_ref(&grown.growth), 1).unwrap();
+            check(name, &[Stroke { curve: &paths.path(0), ..grown.stroke }]);
+        }
+        for (name, script) in [("Turtle flower", FLOWER_SCRIPT), ("Turning squares", SQUARES_SCRIPT), ("Ice crystal", CRYSTAL_SCRIPT)] {
+            let mut vertices = [[0.0; 3]; turtle::CAPACITY];
+            let (drawn, _) = turtle::run(script, &mut vertices);
+            check(name, &drawn.strokes());
+        }
+    }
+}
@@ -0,0 +1,242 @@
+/* Cells of Conway's Game of Life on a torus: cells leaving one edge come back at the opposite one.
+
+   Each row is a bit mask, up to 128 cells across. Generations are computed in place a row at a
+   time, keeping only the old row above, and each row is handed over with what it was so only
+   the cells which were born or died need drawing. */
+
+use crate::random::Random;
+
+pub const MAX_COLUMNS: u32 = u128::BITS;
+pub const MAX_ROWS: usize = 64;
+
+/* Bill Gosper's, sending a glider off every 30 generations */
+pub const GLIDER_GUN: [&str; 9] = [
+    "........................O...........",
+    "......................O.O...........",
+    "............OO......OO............OO",
+    "...........O...O....OO............OO",
+    "OO........O.....O...OO..............",
+    "OO........O...O.OO....O.O...........",
+    "..........O.....O.......O...........",
+    "...........O...O....................",
+    "............OO......................",
+];
+
+/* Oscillates with period 3 */
+pub const PULSAR: [&str; 13] = [
+    "..OOO...OOO..",
+    ".............",
+    "O....O.O....O",
+    "O....O.O....O",
+    "O....O.O....O",
+    "..OOO...OOO..",
+    ".............",
+    "..OOO...OOO..",
+    "O....O.O....O",
+    "O....O.O....O",
+    "O....O.O....O",
+    ".............",
+    "..OOO...OOO..",
+];
+
+pub struct Cells {
+    /* Bit x of row y is the cell in column x */
+    rows: [u128; MAX_ROWS],
+    columns: u32,
+    height: usize,
+}
+
+impl Cells {
+    /* All dead, at most `MAX_COLUMNS` x `MAX_ROWS` */
+    pub fn new(columns: u32, rows: usize) -> Self {
+        Cells {
+            rows: [0; MAX_ROWS],
+            columns: columns.min(MAX_COLUMNS),
+            height: rows.min(MAX_ROWS),
+        }
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.height
+    }
+
+    pub fn row(&self, y: usize) -> u128 {
+        self.rows[y]
+    }
+
+    pub fn is_alive(&self, x: u32, y: usize) -> bool {
+        (self.rows[y] >> x) & 1 == 1
+    }
+
+    /* Each cell alive with chance `density` */
+    pub fn scatter(&mut self, random: &mut Random, density: f32) {
+        for y in 0..self.height {
+            for x in 0..self.columns {
+                if random.chance(density) {
+                    self.rows[y] |= 1 << x;
+                }
+            }
+        }
+    }
+
+    /* `O` for a live cell, centred, cut off where it doesn't fit */
+    pub fn place(&mut self, pattern: &[&str]) {
+        let width = pattern.iter().map(|row| row.len()).max().unwrap_or(0) as u32;
+        let left = self.columns.saturating_sub(width) / 2;
+        let top = self.height.saturating_sub(pattern.len()) / 2;
+        for (y, row) in pattern.iter().enumerate().take(self.height) {
+            for (x, cell) in row.bytes().enumerate() {
+                let x = left + x as u32;
+                if cell == b'O' && x < self.columns {
+                    self.rows[top + y] |= 1 << x;
+                }
+            }
+        }
+    }
+
+    /* Moves on a generation, handing each row over as `changed(y, was, is)` */
+    pub fn step<E, F: FnMut(usize, u128, u128) -> Result<(), E>>(&mut self, mut changed: F) -> Result<(), E> {
+        if self.height == 0 {
+            return Ok(());
+        }
+        /* Row 0 is needed again below the last one, as it was */
+        let first = self.rows[0];
+        let mut above = self.rows[self.height - 1];
+        for y in 0..self.height {
+            let row = self.rows[y];
+            let below = if y + 1 == self.height { first } else { self.rows[y + 1] };
+            let next = self.next_row(above, row, below);
+            self.rows[y] = next;
+            changed(y, row, next)?;
+            above = row;
+        }
+        Ok(())
+    }
+
+    /* Born with 3 neighbours, surviving with 2 or 3 */
+    fn next_row(&self, above: u128, row: u128, below: u128) -> u128 {
+        let alive = |row: u128, x: u32| (row >> x) & 1;
+        let mut next = 0;
+        for x in 0..self.columns {
+            let (left, right) = ((x + self.columns - 1) % self.columns, (x + 1) % self.columns);
+            let neighbours = [above, row, below].iter().map(|&r| alive(r, left) + alive(r, right)).sum::<u128>()
+                + alive(above, x)
+                + alive(below, x);
+            if neighbours == 3 || (neighbours == 2 && alive(row, x) == 1) {
+                next |= 1 << x;
+            }
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{string::String, vec::Vec};
+
+    use super::{Cells, GLIDER_GUN, PULSAR};
+    use crate::random::Random;
+
+    /* A grid just the size of `picture`, so it's placed at (0, 0) */
+    fn cells(picture: &[&str]) -> Cells {
+        let mut cells = Cells::new(picture[0].len() as u32, picture.len());
+        cells.place(picture);
+        cells
+    }
+
+    fn picture(cells: &Cells) -> Vec<String> {
+        (0..cells.rows())
+            .map(|y| (0..cells.columns()).map(|x| if cells.is_alive(x, y) { 'O' } else { '.' }).collect())
+            .collect()
+    }
+
+    fn next(picture: &[&str]) -> Vec<String> {
+        let mut cells = cells(picture);
+        cells.step(|_, _, _| Ok::<(), ()>(())).unwrap();
+        self::picture(&cells)
+    }
+
+    #[test]
+    fn born_with_three_surviving_with_two_or_three() {
+        /* A blinker turns, a block stays */
+        assert_eq!(next(&[".....", ".....", ".OOO.", ".....", "....."]), [".....", "..O..", "..O..", "..O..", "....."]);
+        assert_eq!(next(&["......", ".OO...", ".OO...", "......"]), ["......", ".OO...", ".OO...", "......"]);
+        /* Lonely and overcrowded cells die, two neighbours aren't enough to be born */
+        assert_eq!(next(&[".....", ".O...", ".....", "...O.", "....."]), [".....", ".....", ".....", ".....", "....."]);
+        assert_eq!(next(&[".....", ".OOO.", ".OOO.", ".OOO.", "....."]), ["..O..", ".O.O.", "O...O", ".O.O.", "..O.."]);
+    }
+
+    #[test]
+    fn edges_wrap_around() {
+        /* Across column 0 and columns-1 */
+        assert_eq!(next(&["......", "......", "O....O", "......", "......"]), ["......", "......", "......", "......", "......"]);
+        assert_eq!(next(&["......", "......", "OO...O", "......", "......"]), ["......", "O.....", "O.....", "O.....", "......"]);
+        assert_eq!(next(&["......", "O.....", "O.....", "O.....", "......"]), ["......", "......", "OO...O", "......", "......"]);
+        /* And across the top and bottom rows */
+        assert_eq!(next(&["..O...", "..O...", "......", "......", "..O..."]), [".OOO..", "......", "......", "......", "......"]);
+    }
+
+    /* The same seed fills the same soup */
+    #[test]
+    fn scatter_follows_the_seed() {
+        let soup = |seed| {
+            let mut cells = Cells::new(80, 60);
+            cells.scatter(&mut Random::new(seed), 0.3);
+            picture(&cells)
+        };
+        assert_eq!(soup(7), soup(7));
+        assert_ne!(soup(7), soup(8));
+        let alive = soup(7).iter().map(|row| row.matches('O').count()).sum::<usize>();
+        assert!((1200..1680).contains(&alive), "{alive} of 4800 alive");
+    }
+
+    #[test]
+    fn pulsar_comes_back_after_three() {
+        let mut cells = Cells::new(17, 17);
+        cells.place(&PULSAR);
+        let start = picture(&cells);
+        for generation in 1..=3 {
+            cells.step(|_, _, _| Ok::<(), ()>(())).unwrap();
+            assert_eq!(picture(&cells) == start, generation == 3);
+        }
+    }
+
+    #[test]
+    fn step_hands_over_old_and_new_rows() {
+        let mut cells = cells(&[".....", ".....", ".OOO.", ".....", "....."]);
+        let mut rows = Vec::new();
+        cells
+            .step(|y, was, is| {
+                rows.push((y, was, is));
+                Ok::<(), ()>(())
+            })
+            .unwrap();
+        assert_eq!(rows, [(0, 0, 0), (1, 0, 0b100), (2, 0b1110, 0b100), (3, 0, 0b100), (4, 0, 0)]);
+    }
+
+    #[test]
+    fn patterns_too_big_are_cut_off() {
+        /* The gun is 36x9: its left 10 columns and top 5 rows are all that's left */
+        let mut cells = Cells::new(10, 5);
+        cells.place(&GLIDER_GUN);
+        let expected: Vec<&str> = GLIDER_GUN[..5].iter().map(|row| &row[..10]).collect();
+        assert_eq!(picture(&cells), expected);
+
+        /* Too wide only: centred top to bottom, cut off at the right */
+        let mut cells = Cells::new(12, 13);
+        cells.place(&GLIDER_GUN);
+        let expected: Vec<&str> = (0..13usize).map(|y| GLIDER_GUN.get(y.wrapping_sub(2)).map_or("............", |row| &row[..12])).collect();
+        assert_eq!(picture(&cells), expected);
+
+        /* Nothing to place it in */
+        let mut cells = Cells::new(0, 0);
+        cells.place(&PULSAR);
+        assert_eq!(picture(&cells).len(), 0);
+    }
+}
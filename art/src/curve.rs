@@ -0,0 +1,280 @@
+/* Parametric curves artworks are made of.
+
+   A curve maps a parameter to a point in the units of `Viewport`. `Samples` walks it in even
+   steps of the parameter, or about evenly along the curve, which is all the artworks need to
+   be plotted with glyphs or traced with lines. */
+
+use core::f32::consts::PI;
+use core::ops::Range;
+
+use libm::{roundf, sqrtf};
+
+use crate::trig::{cos, sin};
+
+/* Artworks keep their curves in statics, hence `Sync` */
+pub trait Curve: Sync {
+    fn point(&self, t: f32) -> (f32, f32);
+    /* Parameters the curve is drawn over, the end is left out like in any other range */
+    fn range(&self) -> Range<f32>;
+}
+
+impl dyn Curve + '_ {
+    /* `density` samples per unit of the parameter */
+    pub fn sample(&self, density: f32) -> Samples<'_> {
+        let range = self.range();
+        let length = range.end - range.start;
+        let count = roundf(length * density).max(0.0) as u32;
+        Samples {
+            curve: self,
+            start: range.start,
+            step: if count > 0 { length / count as f32 } else { 0.0 },
+            indices: 0..count,
+            spacing: 0.0,
+            stride: 1,
+            last: None,
+        }
+    }
+}
+
+/* Points at even steps of the parameter, each with its index among them.
+
+   Steps even in the parameter aren't even along the curve: a spiral's samples pile up in the
+   middle and spread out at the edge. `spaced` skips samples so the ones taken are about as far
+   apart as asked. How many to skip is guessed from how far the last skip went, so no sample is
+   computed only to be thrown away. */
+#[derive(Clone)]
+pub struct Samples<'a> {
+    curve: &'a dyn Curve,
+    start: f32,
+    step: f32,
+    indices: Range<u32>,
+    /* Wanted distance between the points taken, 0 takes every one */
+    spacing: f32,
+    /* Indices the next skip goes over, and the point it starts from */
+    stride: u32,
+    last: Option<(f32, f32)>,
+}
+
+impl Samples<'_> {
+    /* The end of the range as well, for curves which close up there */
+    pub fn with_end(mut self) -> Self {
+        self.indices.end += 1;
+        self
+    }
+
+    /* About `spacing` apart along the curve, but never further than the samples themselves */
+    pub fn spaced(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /* Samples not taken or skipped yet, whatever the spacing */
+    pub fn len(&self) -> u32 {
+        self.indices.end - self.indices.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /* Sample `i` counting from the start of the range, whatever has been taken already */
+    pub fn point(&self, i: u32) -> (f32, f32) {
+        self.curve.point(self.start + i as f32 * self.step)
+    }
+}
+
+impl Iterator for Samples<'_> {
+    type Item = (u32, (f32, f32));
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.indices.next()?;
+        let point = self.point(i);
+        if self.spacing > 0.0 {
+            if let Some(last) = self.last {
+                let (dx, dy) = (point.0 - last.0, point.1 - last.1);
+                let distance = sqrtf(dx * dx + dy * dy);
+                /* Growing at most twice over keeps a sudden bend from being skipped */
+                let wanted = if distance > 0.0 { self.stride as f32 * self.spacing / distance } else { f32::MAX };
+                self.stride = (wanted as u32).clamp(1, self.stride * 2);
+            }
+            self.last = Some(point);
+            /* The last sample is always taken, so the curve ends where it should */
+            let end = self.indices.end;
+            self.indices.start = (i + self.stride).min(end.saturating_sub(1).max(i + 1));
+        }
+        Some((i, point))
+    }
+}
+
+/* Archimedean spiral, r = offset + growth·θ, starting at angle `start` (0 points along x, y
+   points down). Different offsets along x and y give an oval one */
+pub struct Spiral {
+    pub centre: (f32, f32),
+    pub offset: (f32, f32),
+    pub growth: f32,
+    pub start: f32,
+    pub clockwise: bool,
+    /* θ, in radians */
+    pub range: Range<f32>,
+}
+
+impl Curve for Spiral {
+    fn point(&self, t: f32) -> (f32, f32) {
+        let angle = if self.clockwise { self.start + t } else { self.start - t };
+        let radius = |offset: f32| offset + self.growth * t;
+        (
+            self.centre.0 + radius(self.offset.0) * cos(angle),
+            self.centre.1 + radius(self.offset.1) * sin(angle),
+        )
+    }
+
+    fn range(&self) -> Range<f32> {
+        self.range.clone()
+    }
+}
+
+/* Rose r = radius·sin(petals·θ). Odd `petals` trace the rose over half a turn, even ones over
+   a whole one, and get twice as many petals */
+#[derive(Clone, Copy)]
+pub struct Rose {
+    pub centre: (f32, f32),
+    pub radius: f32,
+    pub petals: u32,
+}
+
+impl Rose {
+    fn at(&self, angle: f32) -> (f32, f32) {
+        let r = self.radius * sin(self.petals as f32 * angle);
+        (self.centre.0 + r * cos(angle), self.centre.1 + r * sin(angle))
+    }
+}
+
+impl Curve for Rose {
+    fn point(&self, t: f32) -> (f32, f32) {
+        self.at(t)
+    }
+
+    fn range(&self) -> Range<f32> {
+        let turn = if self.petals % 2 == 1 { PI } else { 2.0 * PI };
+        0.0..turn
+    }
+}
+
+/* Maurer rose: points of `rose` `degrees` apart, the parameter counts them */
+pub struct Maurer {
+    pub rose: Rose,
+    pub degrees: f32,
+    pub steps: u32,
+}
+
+impl Curve for Maurer {
+    fn point(&self, t: f32) -> (f32, f32) {
+        /* Whole turns taken off first, a point hundreds of turns around lands as exactly as the first */
+        self.rose.at((t * self.degrees) % 360.0 * (PI / 180.0))
+    }
+
+    fn range(&self) -> Range<f32> {
+        0.0..self.steps as f32
+    }
+}
+
+/* Lissajous figure x = sin(a·t + phase), y = sin(b·t), closing after a whole turn of t */
+#[derive(Clone, Copy)]
+pub struct Lissajous {
+    pub centre: (f32, f32),
+    pub amplitude: (f32, f32),
+    /* a and b */
+    pub frequency: (u32, u32),
+    pub phase: f32,
+}
+
+impl Curve for Lissajous {
+    fn point(&self, t: f32) -> (f32, f32) {
+        (
+            self.centre.0 + self.amplitude.0 * sin(self.frequency.0 as f32 * t + self.phase),
+            self.centre.1 + self.amplitude.1 * sin(self.frequency.1 as f32 * t),
+        )
+    }
+
+    fn range(&self) -> Range<f32> {
+        0.0..2.0 * PI
+    }
+}
+
+/* Spirograph: a wheel rolling inside a ring (a hypotrochoid) or around it (an epitrochoid), with
+   the pen `pen` away from the wheel's centre. Radii count teeth, `tooth` long each, so the pen
+   is back where it started once the wheel has made wheel/gcd(ring, wheel) turns */
+#[derive(Clone, Copy)]
+pub struct Spirograph {
+    pub centre: (f32, f32),
+    pub ring: u32,
+    pub wheel: u32,
+    pub pen: f32,
+    pub outside: bool,
+    pub tooth: f32,
+}
+
+impl Spirograph {
+    /* Farthest the pen gets from the centre, in teeth */
+    pub const fn reach(&self) -> f32 {
+        let arm = if self.outside { self.ring + self.wheel } else { self.ring.abs_diff(self.wheel) };
+        arm as f32 + self.pen
+    }
+
+    /* Fastest the pen moves, in teeth per radian of the wheel's way around the ring */
+    pub const fn speed(&self) -> f32 {
+        let arm = self.reach() - self.pen;
+        arm + self.pen * arm / self.wheel as f32
+    }
+
+    /* Times the wheel goes around the ring before the curve closes */
+    pub const fn turns(&self) -> u32 {
+        self.wheel / gcd(self.ring, self.wheel)
+    }
+}
+
+impl Curve for Spirograph {
+    fn point(&self, t: f32) -> (f32, f32) {
+        let (arm, pen) = if self.outside {
+            ((self.ring + self.wheel) as f32, -self.pen)
+        } else {
+            (self.ring as f32 - self.wheel as f32, self.pen)
+        };
+        /* The wheel spins arm/wheel times for each turn around the ring */
+        let spin = arm / self.wheel as f32 * t;
+        (
+            self.centre.0 + self.tooth * (arm * cos(t) + pen * cos(spin)),
+            self.centre.1 + self.tooth * (arm * sin(t) - self.pen * sin(spin)),
+        )
+    }
+
+    fn range(&self) -> Range<f32> {
+        0.0..2.0 * PI * self.turns() as f32
+    }
+}
+
+const fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/* Straight line, the parameter is the distance from `from` */
+pub struct Segment {
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+}
+
+impl Curve for Segment {
+    fn point(&self, t: f32) -> (f32, f32) {
+        let length = self.range().end;
+        if length == 0.0 {
+            return self.from;
+        }
+        let f = t / length;
+        (self.from.0 + (self.to.0 - self.from.0) * f, self.from.1 + (self.to.1 - self.from.1) * f)
+    }
+
+    fn range(&self) -> Range<f32> {
+        let (dx, dy) = (self.to.0 - self.from.0, self.to.1 - self.from.1);
+        0.0..sqrtf(dx * dx + dy * dy)
+    }
+}
@@ -0,0 +1,71 @@
+/* Colour along a curve.
+
+   Colours are blended on the 5/6/5 bit levels RGB565 actually has, so a gradient changes colour
+   at most 64 times. Renderers only have to switch colour (and the plotter to flush) when it
+   does, not at every sample. */
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+
+#[derive(Clone, Copy)]
+pub enum Gradient<'a> {
+    /* Whatever the theme draws text with */
+    Foreground,
+    Solid(Rgb565),
+    /* Straight blend from the first colour to the second */
+    TwoStop(Rgb565, Rgb565),
+    /* Fully saturated colours around the wheel, in degrees. `to` may go past 360 to wrap around */
+    Hue { from: u16, to: u16 },
+    /* Colours spread evenly, blended with their neighbours */
+    Palette(&'a [Rgb565]),
+}
+
+impl Gradient<'_> {
+    /* `t` runs from 0 at the start of the curve to 1 at its end */
+    pub fn at(&self, t: f32, foreground: Rgb565) -> Rgb565 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Gradient::Foreground => foreground,
+            Gradient::Solid(color) => color,
+            Gradient::TwoStop(from, to) => mix(from, to, t),
+            Gradient::Hue { from, to } => hue(from as f32 + (to as f32 - from as f32) * t),
+            Gradient::Palette(colors) => match colors.len() {
+                0 => foreground,
+                1 => colors[0],
+                count => {
+                    let position = t * (count - 1) as f32;
+                    let i = (position as usize).min(count - 2);
+                    mix(colors[i], colors[i + 1], position - i as f32)
+                }
+            },
+        }
+    }
+}
+
+/* Channel by channel, rounded to the nearest level */
+fn mix(from: Rgb565, to: Rgb565, t: f32) -> Rgb565 {
+    let channel = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t + 0.5) as u8;
+    Rgb565::new(
+        channel(from.r(), to.r()),
+        channel(from.g(), to.g()),
+        channel(from.b(), to.b()),
+    )
+}
+
+fn hue(degrees: f32) -> Rgb565 {
+    let h = degrees % 360.0 / 60.0;
+    let sector = h as u32;
+    let f = h - sector as f32;
+    let (r, g, b) = match sector {
+        0 => (1.0, f, 0.0),
+        1 => (1.0 - f, 1.0, 0.0),
+        2 => (0.0, 1.0, f),
+        3 => (0.0, 1.0 - f, 1.0),
+        4 => (f, 0.0, 1.0),
+        _ => (1.0, 0.0, 1.0 - f),
+    };
+    Rgb565::new(
+        (r * Rgb565::MAX_R as f32 + 0.5) as u8,
+        (g * Rgb565::MAX_G as f32 + 0.5) as u8,
+        (b * Rgb565::MAX_B as f32 + 0.5) as u8,
+    )
+}
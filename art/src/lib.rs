@@ -0,0 +1,28 @@
+/* Everything the gallery draws, without the hardware: curves, brushes and lines, the artworks,
+   scenes and scripts they're made of, themes and strings.
+
+   Nothing here touches a chip, so it builds and tests on the host: `cargo test` in this
+   directory. */
+
+#![no_std]
+
+pub mod animation;
+pub mod artworks;
+pub mod cells;
+pub mod curve;
+pub mod gradient;
+pub mod lsystem;
+pub mod mesh;
+#[cfg(test)]
+mod mock;
+pub mod plotter;
+pub mod polyline;
+pub mod random;
+pub mod scene;
+pub mod scene_file;
+pub mod strings;
+pub mod strip;
+pub mod theme;
+pub mod trig;
+pub mod turtle;
+pub mod viewport;
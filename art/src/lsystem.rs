@@ -0,0 +1,378 @@
+/* L-systems: strings rewritten by rules, drawn by a turtle.
+
+   The string is never built: symbols are rewritten `depth` times recursively and handed to the
+   turtle one by one. F and G draw a step forward, + and − turn by `angle` (+ anticlockwise), [
+   and ] save and restore where the turtle is, other symbols only steer the rewriting.
+
+   Artworks need curves, so a branch isn't left with a jump but walked back along, which keeps
+   the whole drawing one path, each branch drawn there and back. Paths are grown into `Paths`
+   whenever an artwork is opened or a wild garden grown, as seeded variation makes them differ
+   every time. */
+
+use core::fmt;
+use core::ops::Range;
+
+use heapless::Vec;
+use libm::roundf;
+
+use crate::curve::Curve;
+use crate::random::Random;
+use crate::trig::{cos, sin};
+
+pub struct LSystem {
+    pub axiom: &'static str,
+    pub rules: &'static [(char, &'static str)],
+    /* Degrees */
+    pub angle: f32,
+    pub depth: u32,
+}
+
+impl LSystem {
+    pub const PLANT: LSystem = LSystem {
+        axiom: "X",
+        rules: &[('X', "F+[[X]-X]-F[-FX]+X"), ('F', "FF")],
+        angle: 25.0,
+        depth: 4,
+    };
+
+    /* Trunk of `2·depth` steps with a leaf on either side of every other one */
+    pub const STEM: LSystem = LSystem {
+        axiom: "A",
+        rules: &[('A', "F[+G-G]F[-G+G]A")],
+        angle: 35.0,
+        depth: 3,
+    };
+
+    pub const KOCH_SNOWFLAKE: LSystem = LSystem {
+        axiom: "F--F--F",
+        rules: &[('F', "F+F--F+F")],
+        angle: 60.0,
+        depth: 4,
+    };
+
+    pub const DRAGON: LSystem = LSystem {
+        axiom: "FX",
+        rules: &[('X', "X+YF+"), ('Y', "-FX-Y")],
+        angle: 90.0,
+        depth: 10,
+    };
+
+    fn expand<F: FnMut(char)>(&self, visit: &mut F) {
+        expand(self.axiom, self.rules, self.depth, visit);
+    }
+}
+
+fn expand<F: FnMut(char)>(symbols: &str, rules: &[(char, &str)], depth: u32, visit: &mut F) {
+    for symbol in symbols.chars() {
+        match rules.iter().find(|(from, _)| *from == symbol) {
+            Some((_, to)) if depth > 0 => expand(to, rules, depth - 1, visit),
+            _ => visit(symbol),
+        }
+    }
+}
+
+/* How much branches may differ from the rules, turns and steps by up to ± that fraction of
+   theirs. The trunk, outside of any [ ], is always grown as written, so it ends where meant */
+#[derive(Clone, Copy)]
+pub struct Variation {
+    pub angle: f32,
+    pub length: f32,
+    /* Chance a branch is left out */
+    pub prune: f32,
+}
+
+impl Variation {
+    pub const NONE: Variation = Variation { angle: 0.0, length: 0.0, prune: 0.0 };
+}
+
+/* An L-system and where its turtle starts, in the units of `Viewport` */
+pub struct Growth {
+    pub system: LSystem,
+    pub root: (f32, f32),
+    /* Degrees, 0 along x, 90 up */
+    pub heading: f32,
+    pub step: f32,
+    pub variation: Variation,
+}
+
+/* Vertices are in 1/8192ths of the frame height */
+const UNIT: f32 = 8192.0;
+pub const MAX_PATHS: usize = 16;
+
+/* Longest way from the trunk to a branch's tip, in steps, that can be walked back */
+const MAX_REACH: usize = 128;
+const MAX_NESTING: usize = 32;
+
+/* Limit a growth ran into. It's grown without whatever didn't fit */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Overflow {
+    /* More than `MAX_PATHS` growths, the rest aren't grown */
+    Paths,
+    /* More than `N` vertices, the path stops short */
+    Vertices,
+    /* A branch more than `MAX_REACH` steps long, walked back only part of the way */
+    Reach,
+    /* Branches nested more than `MAX_NESTING` deep, left out */
+    Nesting,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Overflow::Paths => write!(f, "more than {} paths", MAX_PATHS),
+            Overflow::Vertices => write!(f, "out of vertices"),
+            Overflow::Reach => write!(f, "branch longer than {} steps", MAX_REACH),
+            Overflow::Nesting => write!(f, "branches nested over {} deep", MAX_NESTING),
+        }
+    }
+}
+
+/* Room for paths of `N` vertices in all, 4 bytes each */
+pub struct Paths<const N: usize> {
+    vertices: [[i16; 2]; N],
+    /* Where each path is among the vertices */
+    paths: Vec<(u16, u16), MAX_PATHS>,
+}
+
+impl<const N: usize> Paths<N> {
+    pub const fn new() -> Self {
+        Paths { vertices: [[0; 2]; N], paths: Vec::new() }
+    }
+
+    /* Grows `growths` into paths 0, 1, ... in place of the ones grown before. Whatever doesn't
+       fit is left out, and the first growth which didn't fit is returned with the limit it hit */
+    pub fn grow(&mut self, growths: &[Growth], seed: u32) -> Result<(), (usize, Overflow)> {
+        let vertices = &mut self.vertices;
+        self.paths.clear();
+        let mut length = 0;
+        let mut overflow = None;
+        for (i, growth) in growths.iter().take(MAX_PATHS).enumerate() {
+            let start = length;
+            /* Plants of one garden shouldn't all look alike */
+            let mut random = Random::new(seed ^ (i as u32 + 1).wrapping_mul(0x9e37_79b9));
+            let mut full = false;
+            let walked = walk(growth, &mut random, &mut |(x, y)| {
+                let vertex = [roundf(x * UNIT) as i16, roundf(y * UNIT) as i16];
+                if length > start && vertices[length - 1] == vertex {
+                    return;
+                }
+                match vertices.get_mut(length) {
+                    Some(slot) => {
+                        *slot = vertex;
+                        length += 1;
+                    }
+                    None => full = true,
+                }
+            });
+            let walked = if full { Err(Overflow::Vertices) } else { walked };
+            if let Err(limit) = walked {
+                overflow = overflow.or(Some((i, limit)));
+            }
+            self.paths.push((start as u16, length as u16)).ok();
+        }
+        if growths.len() > MAX_PATHS {
+            overflow = overflow.or(Some((MAX_PATHS, Overflow::Paths)));
+        }
+        overflow.map_or(Ok(()), Err)
+    }
+
+    /* The `i`-th path grown, empty if there's none */
+    pub fn path(&self, i: usize) -> Path<'_> {
+        match self.paths.get(i) {
+            Some(&(start, end)) => Path(&self.vertices[start as usize..end as usize]),
+            None => Path::NONE,
+        }
+    }
+}
+
+fn walk<F: FnMut((f32, f32))>(growth: &Growth, random: &mut Random, emit: &mut F) -> Result<(), Overflow> {
+    struct Saved {
+        position: (f32, f32),
+        heading: f32,
+        reach: usize,
+    }
+    let variation = growth.variation;
+    /* Degrees, so turns of whole degrees add up exactly */
+    let turn = growth.system.angle;
+    let mut position = growth.root;
+    let mut heading = growth.heading;
+    let mut stack: Vec<Saved, MAX_NESTING> = Vec::new();
+    /* Steps taken since the outermost [ , to be walked back */
+    let mut reach: Vec<(f32, f32), MAX_REACH> = Vec::new();
+    /* Nesting of a pruned branch being skipped */
+    let mut skipping = 0;
+    let mut overflow = None;
+
+    emit(position);
+    growth.system.expand(&mut |symbol| {
+        if skipping > 0 {
+            match symbol {
+                '[' => skipping += 1,
+                ']' => skipping -= 1,
+                _ => {}
+            }
+            return;
+        }
+        let branch = !stack.is_empty();
+        let vary = |random: &mut Random, amount: f32| if branch { 1.0 + amount * random.signed() } else { 1.0 };
+        match symbol {
+            'F' | 'G' => {
+                let step = growth.step * vary(random, variation.length);
+                let angle = heading.to_radians();
+                position = (position.0 + step * cos(angle), position.1 - step * sin(angle));
+                emit(position);
+                if branch && reach.push(position).is_err() {
+                    overflow = overflow.or(Some(Overflow::Reach));
+                }
+            }
+            '+' => heading += turn * vary(random, variation.angle),
+            '-' => heading -= turn * vary(random, variation.angle),
+            '[' if random.chance(variation.prune) => skipping = 1,
+            '[' => {
+                let saved = Saved { position, heading, reach: reach.len() };
+                if stack.push(saved).is_err() {
+                    skipping = 1;
+                    overflow = overflow.or(Some(Overflow::Nesting));
+                }
+            }
+            ']' => {
+                if let Some(saved) = stack.pop() {
+                    /* Back along the branch to where it started */
+                    while reach.len() > saved.reach {
+                        reach.pop();
+                        if let Some(&back) = reach.last().filter(|_| reach.len() > saved.reach) {
+                            emit(back);
+                        }
+                    }
+                    emit(saved.position);
+                    (position, heading) = (saved.position, saved.heading);
+                }
+            }
+            _ => {}
+        }
+    });
+    overflow.map_or(Ok(()), Err)
+}
+
+impl<const N: usize> Default for Paths<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* Path grown by `Paths::grow`, the parameter counts its vertices */
+#[derive(Clone, Copy)]
+pub struct Path<'a>(&'a [[i16; 2]]);
+
+impl Path<'_> {
+    /* Draws nothing, stands in for a path until it's grown */
+    pub const NONE: Path<'static> = Path(&[]);
+}
+
+impl Curve for Path<'_> {
+    fn point(&self, t: f32) -> (f32, f32) {
+        let vertices = self.0;
+        let Some(last) = vertices.len().checked_sub(1) else {
+            return (0.0, 0.0);
+        };
+        let i = (t.max(0.0) as usize).min(last);
+        let (from, to) = (vertices[i], vertices[(i + 1).min(last)]);
+        let f = t - i as f32;
+        let at = |a: i16, b: i16| (a as f32 + (b as f32 - a as f32) * f) / UNIT;
+        (at(from[0], to[0]), at(from[1], to[1]))
+    }
+
+    fn range(&self) -> Range<f32> {
+        0.0..self.0.len().saturating_sub(1) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Growth, LSystem, Overflow, Paths, Variation, MAX_PATHS, UNIT};
+    use crate::artworks::{design, stem, DRAGON, KOCH_SNOWFLAKE, PATH_VERTICES, PLANT, STEM_VERTICES};
+
+    /* Where path `i` starts and ends, in vertex units */
+    fn ends<const N: usize>(paths: &Paths<N>, i: usize) -> ([i16; 2], [i16; 2]) {
+        let vertices = paths.path(i).0;
+        (vertices[0], vertices[vertices.len() - 1])
+    }
+
+    fn near(a: [i16; 2], b: (f32, f32)) -> bool {
+        (a[0] as f32 - b.0 * UNIT).abs() <= 2.0 && (a[1] as f32 - b.1 * UNIT).abs() <= 2.0
+    }
+
+    #[test]
+    fn koch_snowflake_closes() {
+        let mut paths: Paths<PATH_VERTICES> = Paths::new();
+        assert_eq!(paths.grow(core::slice::from_ref(&KOCH_SNOWFLAKE.growth), 1), Ok(()));
+        /* 3 sides of 4^depth steps each */
+        assert_eq!(paths.path(0).0.len(), 3 * 4usize.pow(LSystem::KOCH_SNOWFLAKE.depth) + 1);
+        let (first, last) = ends(&paths, 0);
+        assert!(near(first, KOCH_SNOWFLAKE.growth.root) && near(last, KOCH_SNOWFLAKE.growth.root));
+    }
+
+    #[test]
+    fn dragon_ends_a_quarter_turn_off() {
+        let mut paths: Paths<PATH_VERTICES> = Paths::new();
+        assert_eq!(paths.grow(core::slice::from_ref(&DRAGON.growth), 1), Ok(()));
+        assert_eq!(paths.path(0).0.len(), 2usize.pow(LSystem::DRAGON.depth) + 1);
+        /* Every rewrite turns the end by 45° and takes it √2 further: 10 of them, 32 steps up */
+        let (first, last) = ends(&paths, 0);
+        let (x, y) = DRAGON.growth.root;
+        assert!(near(first, (x, y)));
+        assert!(near(last, (x, y - 32.0 * DRAGON.growth.step)));
+    }
+
+    /* Whatever the seed, the presets fit where the gallery grows them */
+    #[test]
+    fn presets_fit() {
+        let mut paths: Paths<PATH_VERTICES> = Paths::new();
+        for seed in 0..256 {
+            for grown in [&PLANT, &KOCH_SNOWFLAKE, &DRAGON] {
+                assert_eq!(paths.grow(core::slice::from_ref(&grown.growth), seed), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn unpruned_stem_is_the_longest() {
+        let mut paths: Paths<PATH_VERTICES> = Paths::new();
+        let unpruned = Growth { variation: Variation { prune: 0.0, ..stem(160.0, 100.0, 100.0).variation }, ..stem(160.0, 100.0, 100.0) };
+        assert_eq!(paths.grow(core::slice::from_ref(&unpruned), 1), Ok(()));
+        assert_eq!(paths.path(0).0.len(), STEM_VERTICES);
+        for seed in 0..256 {
+            assert_eq!(paths.grow(core::slice::from_ref(&stem(160.0, 100.0, 100.0)), seed), Ok(()));
+            assert!(paths.path(0).0.len() <= STEM_VERTICES);
+        }
+    }
+
+    fn growth(axiom: &'static str, rules: &'static [(char, &'static str)], depth: u32) -> Growth {
+        Growth {
+            system: LSystem { axiom, rules, angle: 90.0, depth },
+            root: design(160.0, 120.0),
+            heading: 0.0,
+            step: 1.0 / UNIT,
+            variation: Variation::NONE,
+        }
+    }
+
+    #[test]
+    fn overflows_are_reported() {
+        let mut small: Paths<100> = Paths::new();
+        assert_eq!(small.grow(core::slice::from_ref(&KOCH_SNOWFLAKE.growth), 1), Err((0, Overflow::Vertices)));
+        assert_eq!(small.path(0).0.len(), 100);
+
+        let mut paths: Paths<PATH_VERTICES> = Paths::new();
+        /* A branch of 256 steps */
+        let long = [growth("F", &[], 0), growth("[F]", &[('F', "FF")], 8)];
+        assert_eq!(paths.grow(&long, 1), Err((1, Overflow::Reach)));
+        /* Branches in branches, 41 deep */
+        let deep = growth("[X]", &[('X', "F[X]")], 40);
+        assert_eq!(paths.grow(core::slice::from_ref(&deep), 1), Err((0, Overflow::Nesting)));
+
+        let many: [Growth; MAX_PATHS + 1] = core::array::from_fn(|_| growth("F", &[], 0));
+        assert_eq!(paths.grow(&many, 1), Err((MAX_PATHS, Overflow::Paths)));
+        assert_eq!(paths.path(MAX_PATHS - 1).0.len(), 2);
+    }
+}
@@ -0,0 +1,143 @@
+/* Wireframe meshes and the little 3D it takes to show them.
+
+   A mesh is a list of vertices and the edges between them. Vertices are rotated by a matrix,
+   then projected in perspective from a camera looking down the z axis, which leaves the
+   points in the units of `Viewport` and how far away they are. Models fit a sphere of radius 1
+   around the origin. */
+
+use crate::trig::{cos, sin};
+
+pub struct Mesh {
+    pub vertices: &'static [[f32; 3]],
+    pub edges: &'static [[u8; 2]],
+}
+
+/* Corners of a cube, on the unit sphere */
+const C: f32 = 0.57735027;
+
+pub static CUBE: Mesh = Mesh {
+    vertices: &[
+        [-C, -C, -C], [C, -C, -C], [-C, C, -C], [C, C, -C],
+        [-C, -C, C], [C, -C, C], [-C, C, C], [C, C, C],
+    ],
+    /* Corners one bit of the index apart */
+    edges: &[
+        [0, 1], [2, 3], [4, 5], [6, 7],
+        [0, 2], [1, 3], [4, 6], [5, 7],
+        [0, 4], [1, 5], [2, 6], [3, 7],
+    ],
+};
+
+/* (0, ±1, ±φ) and its cyclic permutations, scaled down onto the unit sphere */
+const A: f32 = 0.5257311;
+const B: f32 = 0.8506508;
+
+pub static ICOSAHEDRON: Mesh = Mesh {
+    vertices: &[
+        [-A, B, 0.0], [A, B, 0.0], [-A, -B, 0.0], [A, -B, 0.0],
+        [0.0, -A, B], [0.0, A, B], [0.0, -A, -B], [0.0, A, -B],
+        [B, 0.0, -A], [B, 0.0, A], [-B, 0.0, -A], [-B, 0.0, A],
+    ],
+    edges: &[
+        [0, 1], [0, 5], [0, 7], [0, 10], [0, 11], [1, 5], [1, 7], [1, 8], [1, 9], [2, 3],
+        [2, 4], [2, 6], [2, 10], [2, 11], [3, 4], [3, 6], [3, 8], [3, 9], [4, 5], [4, 9],
+        [4, 11], [5, 9], [5, 11], [6, 7], [6, 8], [6, 10], [7, 8], [7, 10], [8, 9], [10, 11],
+    ],
+};
+
+/* Rings of the tube around the hole, and vertices around each ring */
+const RINGS: usize = 12;
+const SIDES: usize = 6;
+const TUBE: f32 = 0.3;
+
+pub static TORUS: Mesh = Mesh {
+    vertices: &torus_vertices(),
+    edges: &torus_edges(),
+};
+
+/* cos and sin of k twelfths of a turn */
+const TWELFTHS: [(f32, f32); 12] = [
+    (1.0, 0.0), (0.8660254, 0.5), (0.5, 0.8660254), (0.0, 1.0),
+    (-0.5, 0.8660254), (-0.8660254, 0.5), (-1.0, 0.0), (-0.8660254, -0.5),
+    (-0.5, -0.8660254), (0.0, -1.0), (0.5, -0.8660254), (0.8660254, -0.5),
+];
+
+/* Lying in the x-z plane, vertex `ring·SIDES + side` */
+const fn torus_vertices() -> [[f32; 3]; RINGS * SIDES] {
+    let mut vertices = [[0.0; 3]; RINGS * SIDES];
+    let mut ring = 0;
+    while ring < RINGS {
+        let (cos_ring, sin_ring) = TWELFTHS[ring * 12 / RINGS];
+        let mut side = 0;
+        while side < SIDES {
+            let (cos_side, sin_side) = TWELFTHS[side * 12 / SIDES];
+            let reach = 1.0 - TUBE + TUBE * cos_side;
+            vertices[ring * SIDES + side] = [reach * cos_ring, TUBE * sin_side, reach * sin_ring];
+            side += 1;
+        }
+        ring += 1;
+    }
+    vertices
+}
+
+/* Around each ring, and along the tube to the next one */
+const fn torus_edges() -> [[u8; 2]; 2 * RINGS * SIDES] {
+    let mut edges = [[0; 2]; 2 * RINGS * SIDES];
+    let mut ring = 0;
+    while ring < RINGS {
+        let mut side = 0;
+        while side < SIDES {
+            let vertex = (ring * SIDES + side) as u8;
+            let around = (ring * SIDES + (side + 1) % SIDES) as u8;
+            let along = ((ring + 1) % RINGS * SIDES + side) as u8;
+            edges[2 * (ring * SIDES + side)] = [vertex, around];
+            edges[2 * (ring * SIDES + side) + 1] = [vertex, along];
+            side += 1;
+        }
+        ring += 1;
+    }
+    edges
+}
+
+/* Rotation, rows times column vectors */
+#[derive(Clone, Copy)]
+pub struct Matrix([[f32; 3]; 3]);
+
+impl Matrix {
+    /* Anticlockwise by `angle` radians, looking down the axis from its positive end */
+    pub fn rotation(axis: usize, angle: f32) -> Self {
+        let (c, s) = (cos(angle), sin(angle));
+        let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+        let mut rows = [[0.0; 3]; 3];
+        rows[axis][axis] = 1.0;
+        rows[a][a] = c;
+        rows[a][b] = -s;
+        rows[b][a] = s;
+        rows[b][b] = c;
+        Matrix(rows)
+    }
+
+    /* `other` first, then `self` */
+    pub fn then(&self, other: &Matrix) -> Matrix {
+        let mut rows = [[0.0; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| self.0[i][k] * other.0[k][j]).sum();
+            }
+        }
+        Matrix(rows)
+    }
+
+    pub fn apply(&self, v: [f32; 3]) -> [f32; 3] {
+        let row = |r: [f32; 3]| r[0] * v[0] + r[1] * v[1] + r[2] * v[2];
+        [row(self.0[0]), row(self.0[1]), row(self.0[2])]
+    }
+}
+
+/* Camera `distance` away from the origin on the negative z side, y up. Points at the origin's
+   depth are shown `scale` units from `centre` per unit, nearer ones bigger. Returns the point
+   and its depth, from -1 nearest to 1 furthest on a model */
+pub fn project(v: [f32; 3], distance: f32, centre: (f32, f32), scale: f32) -> ((f32, f32), f32) {
+    let perspective = scale * distance / (distance + v[2]);
+    ((centre.0 + v[0] * perspective, centre.1 - v[1] * perspective), v[2])
+}
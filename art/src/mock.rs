@@ -0,0 +1,66 @@
+/* A screen in memory for the tests: what ends up on it and how it got there */
+
+extern crate std;
+
+use core::convert::Infallible;
+use std::vec::Vec;
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+pub struct Screen {
+    pub size: Size,
+    pub pixels: Vec<Rgb565>,
+    /* Pixels sent, counting the ones sent again */
+    pub writes: usize,
+    /* Areas filled with `fill_solid`, in order */
+    pub fills: Vec<Rectangle>,
+}
+
+impl Screen {
+    pub fn new(width: u32, height: u32, background: Rgb565) -> Self {
+        Screen {
+            size: Size::new(width, height),
+            pixels: std::vec![background; (width * height) as usize],
+            writes: 0,
+            fills: Vec::new(),
+        }
+    }
+
+    pub fn pixel(&self, x: i32, y: i32) -> Rgb565 {
+        self.pixels[(y * self.size.width as i32 + x) as usize]
+    }
+
+    /* Pixels which aren't `background` */
+    pub fn lit(&self, background: Rgb565) -> usize {
+        self.pixels.iter().filter(|&&color| color != background).count()
+    }
+}
+
+impl OriginDimensions for Screen {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for Screen {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if self.bounding_box().contains(point) {
+                self.pixels[(point.y * self.size.width as i32 + point.x) as usize] = color;
+                self.writes += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.fills.push(*area);
+        self.draw_iter(area.points().map(|point| Pixel(point, color)))
+    }
+}
@@ -0,0 +1,369 @@
+/* Brush based point plotter.
+
+   Drawing a text glyph per curve sample costs a font lookup and one SPI window per lit pixel.
+   The plotter stamps a precomputed brush mask into a 1 bit per pixel screen mask instead and
+   draws the mask on `flush()`: every horizontal run of set bits becomes one `fill_solid` call,
+   i.e. one SPI window and a single burst of pixels. Curve samples overlap a lot, so each pixel
+   is sent once no matter how many samples hit it. A second mask remembers what has been sent
+   since the colour last changed, so flushing often, e.g. once per animation frame, doesn't send
+   the pixels a stamp shares with earlier ones again either. */
+
+use core::convert::Infallible;
+use core::ops::Range;
+
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Alignment, Text},
+};
+
+/* Up to 32x32 pixel mask, bit `x` of `rows[y]` is the pixel (x, y). `hotspot` lands on the plotted point */
+#[derive(Clone, Copy)]
+pub struct Brush {
+    rows: [u32; 32],
+    size: Size,
+    hotspot: Point,
+}
+
+impl Brush {
+    pub const MAX_SIZE: u32 = 32;
+
+    pub fn pixel() -> Self {
+        let mut rows = [0; 32];
+        rows[0] = 1;
+        Brush {
+            rows,
+            size: Size::new(1, 1),
+            hotspot: Point::zero(),
+        }
+    }
+
+    /* Filled circle, centred on the plotted point */
+    pub fn disc(diameter: u32) -> Self {
+        let diameter = diameter.clamp(1, Self::MAX_SIZE);
+        let mut rows = [0; 32];
+        /* Same test as embedded-graphics uses for circles: distance from the centre, in half pixels */
+        let threshold = (diameter * diameter) as i32;
+        for y in 0..diameter as i32 {
+            for x in 0..diameter as i32 {
+                let dx = 2 * x + 1 - diameter as i32;
+                let dy = 2 * y + 1 - diameter as i32;
+                if dx * dx + dy * dy < threshold {
+                    rows[y as usize] |= 1 << x;
+                }
+            }
+        }
+        Brush {
+            rows,
+            size: Size::new(diameter, diameter),
+            hotspot: Point::new(diameter as i32 / 2, diameter as i32 / 2),
+        }
+    }
+
+    /* Arbitrary bitmap, one `u32` per row */
+    pub fn bitmap(rows: &[u32], width: u32, hotspot: Point) -> Self {
+        let mut brush = Brush {
+            rows: [0; 32],
+            size: Size::new(width.min(Self::MAX_SIZE), (rows.len() as u32).min(Self::MAX_SIZE)),
+            hotspot,
+        };
+        for (dst, src) in brush.rows.iter_mut().zip(rows) {
+            *dst = *src & Self::row_mask(brush.size.width);
+        }
+        brush
+    }
+
+    /* Exactly the pixels `Text::with_alignment(glyph, point, font, Alignment::Center)` would draw */
+    pub fn glyph(font: &MonoFont, glyph: char) -> Self {
+        let hotspot = Point::new(16, 24);
+        let mut mask = Mask { rows: [0; 32] };
+        let mut buffer = [0; 4];
+        Text::with_alignment(glyph.encode_utf8(&mut buffer), hotspot, MonoTextStyle::new(font, Rgb565::BLACK), Alignment::Center)
+            .draw(&mut mask)
+            .ok();
+        Brush {
+            rows: mask.rows,
+            size: Size::new(Self::MAX_SIZE, Self::MAX_SIZE),
+            hotspot,
+        }
+        .trimmed()
+    }
+
+    /* Drops empty rows and columns around the mask */
+    fn trimmed(self) -> Self {
+        let used = &self.rows[..self.size.height as usize];
+        let (top, bottom) = match (used.iter().position(|row| *row != 0), used.iter().rposition(|row| *row != 0)) {
+            (Some(top), Some(bottom)) => (top, bottom),
+            _ => return Brush { size: Size::zero(), ..self },
+        };
+        let columns = used.iter().fold(0, |acc, row| acc | row);
+        let left = columns.trailing_zeros();
+        let right = 31 - columns.leading_zeros();
+
+        let mut rows = [0; 32];
+        for (dst, src) in rows.iter_mut().zip(&used[top..=bottom]) {
+            *dst = src >> left;
+        }
+        Brush {
+            rows,
+            size: Size::new(right - left + 1, (bottom - top + 1) as u32),
+            hotspot: self.hotspot - Point::new(left as i32, top as i32),
+        }
+    }
+
+    fn row_mask(width: u32) -> u32 {
+        if width >= 32 { u32::MAX } else { (1 << width) - 1 }
+    }
+}
+
+/* Collects the pixels of a glyph */
+struct Mask {
+    rows: [u32; 32],
+}
+
+impl OriginDimensions for Mask {
+    fn size(&self) -> Size {
+        Size::new(Brush::MAX_SIZE, Brush::MAX_SIZE)
+    }
+}
+
+impl DrawTarget for Mask {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, _) in pixels {
+            if (0..32).contains(&point.x) && (0..32).contains(&point.y) {
+                self.rows[point.y as usize] |= 1 << point.x;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* 1 bit per pixel, enough for a whole 320x320 screen. Banded rendering keeps only 16 rows at a
+   time, so there the masks are just tall enough for any brush and follow the pen, 1.25 KB each */
+#[cfg(not(feature = "strip"))]
+const MASK_WORDS: usize = 320 * 320 / 32;
+#[cfg(feature = "strip")]
+const MASK_WORDS: usize = 320 * Brush::MAX_SIZE as usize / 32;
+
+/* Made once for everything drawn into one target, which is passed to each call rather than
+   borrowed, so an animation keeps its plotter from frame to frame */
+pub struct Plotter {
+    brush: Brush,
+    color: Rgb565,
+    mask: [u32; MASK_WORDS],
+    /* Pixels already on the target in `color`, same layout as `mask` */
+    sent: [u32; MASK_WORDS],
+    /* Mask covers `width` x `rows` pixels from `origin`, `stride` words per row */
+    origin: Point,
+    width: u32,
+    stride: usize,
+    rows: i32,
+    /* Whether the mask covers the target from top to bottom and never has to move */
+    whole_height: bool,
+    /* Rows plotted on since the last flush, the rest of the mask is clear */
+    dirty: Range<i32>,
+    /* Rows of `sent` which have any bits set */
+    sent_rows: Range<i32>,
+}
+
+impl Plotter {
+    pub fn new(area: Rectangle, brush: Brush, color: Rgb565) -> Self {
+        let stride = area.size.width.div_ceil(32).max(1) as usize;
+        let rows = (MASK_WORDS / stride) as i32;
+        Plotter {
+            brush,
+            color,
+            mask: [0; MASK_WORDS],
+            sent: [0; MASK_WORDS],
+            origin: area.top_left,
+            width: area.size.width,
+            stride,
+            rows,
+            whole_height: rows >= area.size.height as i32,
+            dirty: 0..0,
+            sent_rows: 0..0,
+        }
+    }
+
+    pub fn set_brush(&mut self, brush: Brush) {
+        self.brush = brush;
+    }
+
+    /* Mask holds a single colour, so pending pixels are drawn first. Whatever the new colour
+       lands on has to be sent again */
+    pub fn set_color<D: DrawTarget<Color = Rgb565>>(&mut self, target: &mut D, color: Rgb565) -> Result<(), D::Error> {
+        if color != self.color {
+            self.flush(target)?;
+            self.forget_sent();
+            self.color = color;
+        }
+        Ok(())
+    }
+
+    fn forget_sent(&mut self) {
+        let rows = core::mem::replace(&mut self.sent_rows, 0..0);
+        if !rows.is_empty() {
+            self.sent[rows.start as usize * self.stride..rows.end as usize * self.stride].fill(0);
+        }
+    }
+
+    pub fn plot<D: DrawTarget<Color = Rgb565>>(&mut self, target: &mut D, point: Point) -> Result<(), D::Error> {
+        let top_left = point - self.brush.hotspot;
+        let height = self.brush.size.height as i32;
+        if !self.whole_height && (top_left.y < self.origin.y || top_left.y + height > self.origin.y + self.rows) {
+            /* Tall screen: move the mask so it is centred on the stamp */
+            self.flush(target)?;
+            self.forget_sent();
+            self.origin.y = top_left.y + height / 2 - self.rows / 2;
+        }
+
+        let offset = top_left - self.origin;
+        for (y, row) in self.brush.rows[..height as usize].iter().enumerate() {
+            let y = offset.y + y as i32;
+            if y < 0 || y >= self.rows || *row == 0 {
+                continue;
+            }
+            let (bits, x) = match offset.x {
+                x if x <= -32 => continue,
+                x if x < 0 => (*row >> -x, 0),
+                x => (*row, x as usize),
+            };
+            let word = x / 32;
+            let shifted = (bits as u64) << (x % 32);
+            let line = &mut self.mask[y as usize * self.stride..(y as usize + 1) * self.stride];
+            if word < self.stride {
+                line[word] |= shifted as u32;
+            }
+            if word + 1 < self.stride {
+                line[word + 1] |= (shifted >> 32) as u32;
+            }
+            self.dirty = if self.dirty.is_empty() { y..y + 1 } else { self.dirty.start.min(y)..self.dirty.end.max(y + 1) };
+        }
+
+        Ok(())
+    }
+
+    /* Draws everything plotted since the last flush and not sent in this colour yet, one
+       `fill_solid` per horizontal run, and clears the rows it went through. Must be called once
+       plotting is done. */
+    pub fn flush<D: DrawTarget<Color = Rgb565>>(&mut self, target: &mut D) -> Result<(), D::Error> {
+        let dirty = core::mem::replace(&mut self.dirty, 0..0);
+        if !dirty.is_empty() {
+            self.sent_rows = if self.sent_rows.is_empty() {
+                dirty.clone()
+            } else {
+                self.sent_rows.start.min(dirty.start)..self.sent_rows.end.max(dirty.end)
+            };
+        }
+        for y in dirty {
+            let y = y as usize;
+            let mut start = None;
+            for w in 0..self.stride {
+                let i = y * self.stride + w;
+                let bits = core::mem::take(&mut self.mask[i]) & !self.sent[i];
+                self.sent[i] |= bits;
+                let base = w as u32 * 32;
+                let mut position = 0;
+                while position < 32 {
+                    /* Look for the next set bit, or for the end of the current run */
+                    let rest = match start {
+                        None => bits >> position,
+                        Some(_) => !bits >> position,
+                    };
+                    if rest == 0 {
+                        break;
+                    }
+                    position += rest.trailing_zeros();
+                    match start.take() {
+                        None => start = Some(base + position),
+                        Some(from) => self.fill_run(target, y as i32, from, base + position)?,
+                    }
+                }
+            }
+            if let Some(from) = start {
+                self.fill_run(target, y as i32, from, self.stride as u32 * 32)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fill_run<D: DrawTarget<Color = Rgb565>>(&self, target: &mut D, y: i32, from: u32, to: u32) -> Result<(), D::Error> {
+        let to = to.min(self.width);
+        if from >= to {
+            return Ok(());
+        }
+        target.fill_solid(
+            &Rectangle::new(self.origin + Point::new(from as i32, y), Size::new(to - from, 1)),
+            self.color,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+    use super::{Brush, Plotter};
+    use crate::mock::Screen;
+
+    /* Runs `brush` stamped at (10, 10) as `(y, x, width)`, in the order they were sent */
+    fn runs(brush: Brush) -> std::vec::Vec<(i32, i32, u32)> {
+        let mut screen = Screen::new(32, 32, Rgb565::WHITE);
+        let mut plotter = Plotter::new(screen.bounding_box(), brush, Rgb565::BLACK);
+        plotter.plot(&mut screen, Point::new(10, 10)).unwrap();
+        plotter.flush(&mut screen).unwrap();
+        assert_eq!(screen.writes, screen.lit(Rgb565::WHITE), "pixels sent twice");
+        screen
+            .fills
+            .iter()
+            .map(|&Rectangle { top_left, size }| {
+                assert_eq!(size.height, 1, "runs are single rows");
+                (top_left.y, top_left.x, size.width)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pixel_is_one_pixel() {
+        assert_eq!(runs(Brush::pixel()), [(10, 10, 1)]);
+    }
+
+    #[test]
+    fn disc_is_a_run_per_row() {
+        assert_eq!(runs(Brush::disc(5)), [(8, 9, 3), (9, 8, 5), (10, 8, 5), (11, 8, 5), (12, 9, 3)]);
+        assert_eq!(runs(Brush::disc(2)), [(9, 9, 2), (10, 9, 2)]);
+    }
+
+    #[test]
+    fn bitmap_runs_break_at_gaps() {
+        /* Bit x is column x, the hotspot is the middle of the top row */
+        assert_eq!(runs(Brush::bitmap(&[0b101, 0b111], 3, Point::new(1, 0))), [(10, 9, 1), (10, 11, 1), (11, 9, 3)]);
+        /* Bits past the width are dropped */
+        assert_eq!(runs(Brush::bitmap(&[0b1111], 2, Point::zero())), [(10, 10, 2)]);
+    }
+
+    #[test]
+    fn flush_sends_only_what_was_plotted_since() {
+        let mut screen = Screen::new(320, 240, Rgb565::WHITE);
+        let mut plotter = Plotter::new(screen.bounding_box(), Brush::disc(3), Rgb565::BLACK);
+        plotter.plot(&mut screen, Point::new(10, 10)).unwrap();
+        plotter.flush(&mut screen).unwrap();
+        let first = screen.fills.len();
+        plotter.plot(&mut screen, Point::new(300, 200)).unwrap();
+        plotter.flush(&mut screen).unwrap();
+        assert!(screen.fills[first..].iter().all(|fill| fill.top_left.y >= 199));
+        plotter.flush(&mut screen).unwrap();
+        assert_eq!(screen.fills.len(), 2 * first);
+    }
+}
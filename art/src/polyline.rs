@@ -0,0 +1,176 @@
+/* Anti-aliased lines, after Xiaolin Wu.
+
+   Every column (or row, for steep lines) gets the pixels the line passes through, each shaded by
+   how much of it the line covers. The display can't be read back, so partly covered pixels are
+   blended against a known background colour rather than against what's already there. Lines of
+   one polyline remember which pixels they covered at least half, so where the polyline crosses
+   itself the faint fringes of one line don't cut into another. */
+
+use core::ops::Range;
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+use libm::{floorf, roundf, sqrtf};
+
+#[derive(Clone, Copy)]
+pub struct Pen {
+    pub width: f32,
+    pub color: Rgb565,
+    pub background: Rgb565,
+}
+
+/* `alpha` of `color` over `background`, `alpha` is 0..=32 */
+pub fn blend(color: Rgb565, background: Rgb565, alpha: u8) -> Rgb565 {
+    let alpha = alpha.min(32) as u16;
+    let mix = |over: u8, under: u8| ((over as u16 * alpha + under as u16 * (32 - alpha) + 16) / 32) as u8;
+    Rgb565::new(
+        mix(color.r(), background.r()),
+        mix(color.g(), background.g()),
+        mix(color.b(), background.b()),
+    )
+}
+
+/* Pixels the line passes through and how much of each it covers, 0..=32. Both ends are
+   included, so consecutive segments of a polyline meet without a gap */
+fn coverage(from: (f32, f32), to: (f32, f32), width: f32) -> impl Iterator<Item = (Point, u8)> {
+    let ((mut x0, mut y0), (mut x1, mut y1)) = (from, to);
+    /* Walk along the longer axis, one step per pixel */
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    if steep {
+        (x0, y0, x1, y1) = (y0, x0, y1, x1);
+    }
+    if x0 > x1 {
+        (x0, y0, x1, y1) = (x1, y1, x0, y0);
+    }
+
+    let gradient = if x1 > x0 { (y1 - y0) / (x1 - x0) } else { 0.0 };
+    /* How much of the short axis the line spans at each step */
+    let half = width.max(1.0) * sqrtf(1.0 + gradient * gradient) / 2.0;
+
+    (roundf(x0) as i32..=roundf(x1) as i32).flat_map(move |x| {
+        let centre = y0 + gradient * (x as f32 - x0);
+        let (low, high) = (centre - half, centre + half);
+        /* Pixel `y` covers y - 0.5..y + 0.5 */
+        (floorf(low + 0.5) as i32..=floorf(high + 0.5) as i32).filter_map(move |y| {
+            let coverage = high.min(y as f32 + 0.5) - low.max(y as f32 - 0.5);
+            let alpha = roundf(coverage * 32.0) as i32;
+            if alpha <= 0 {
+                return None;
+            }
+            let point = if steep { Point::new(y, x) } else { Point::new(x, y) };
+            Some((point, alpha.min(32) as u8))
+        })
+    })
+}
+
+/* A single line, its fringes blended against `pen.background` whatever is under them */
+pub fn draw_line<D: DrawTarget<Color = Rgb565>>(target: &mut D, from: (f32, f32), to: (f32, f32), pen: Pen) -> Result<(), D::Error> {
+    target.draw_iter(coverage(from, to, pen.width).map(|(point, alpha)| Pixel(point, blend(pen.color, pen.background, alpha))))
+}
+
+/* 1 bit per pixel, enough for a whole 320x320 screen */
+const MASK_WORDS: usize = 320 * 320 / 32;
+
+/* Lines of one polyline. Pixels a line covers at least half are marked, and the fainter fringes
+   of later lines leave them alone instead of blending them back towards the background. Made
+   once for everything drawn into one target, `restart` begins the next polyline */
+pub struct Polyline {
+    covered: [u32; MASK_WORDS],
+    /* Mask covers `stride` words per row of `rows` rows from `origin` */
+    origin: Point,
+    stride: usize,
+    rows: i32,
+    /* Rows with marks, the rest of the mask is clear */
+    dirty: Range<i32>,
+}
+
+impl Polyline {
+    pub fn new(area: Rectangle) -> Self {
+        let stride = area.size.width.div_ceil(32).max(1) as usize;
+        Polyline {
+            covered: [0; MASK_WORDS],
+            origin: area.top_left,
+            stride,
+            rows: (area.size.height as i32).min((MASK_WORDS / stride) as i32),
+            dirty: 0..0,
+        }
+    }
+
+    pub fn restart(&mut self) {
+        for y in core::mem::replace(&mut self.dirty, 0..0) {
+            let y = y as usize;
+            self.covered[y * self.stride..(y + 1) * self.stride].fill(0);
+        }
+    }
+
+    pub fn draw_line<D: DrawTarget<Color = Rgb565>>(&mut self, target: &mut D, from: (f32, f32), to: (f32, f32), pen: Pen) -> Result<(), D::Error> {
+        let pixels = coverage(from, to, pen.width).filter_map(|(point, alpha)| {
+            let offset = point - self.origin;
+            let columns = self.stride as i32 * 32;
+            if (0..columns).contains(&offset.x) && (0..self.rows).contains(&offset.y) {
+                let (word, bit) = (offset.y as usize * self.stride + offset.x as usize / 32, 1 << (offset.x % 32));
+                if alpha >= 16 {
+                    self.covered[word] |= bit;
+                    self.dirty = if self.dirty.is_empty() { offset.y..offset.y + 1 } else { self.dirty.start.min(offset.y)..self.dirty.end.max(offset.y + 1) };
+                } else if self.covered[word] & bit != 0 {
+                    return None;
+                }
+            }
+            Some(Pixel(point, blend(pen.color, pen.background, alpha)))
+        });
+        target.draw_iter(pixels)
+    }
+
+    /* Joins consecutive points as a new polyline, each segment in the colour of the point it leads to */
+    pub fn draw<D, I>(&mut self, target: &mut D, points: I, pen: Pen) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+        I: IntoIterator<Item = ((f32, f32), Rgb565)>,
+    {
+        self.restart();
+        let mut last = None;
+        for (point, color) in points {
+            if let Some(from) = last {
+                self.draw_line(target, from, point, Pen { color, ..pen })?;
+            }
+            last = Some(point);
+        }
+        Ok(())
+    }
+}
+
+pub fn draw_polyline<D, I>(target: &mut D, points: I, pen: Pen) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+    I: IntoIterator<Item = ((f32, f32), Rgb565)>,
+{
+    Polyline::new(target.bounding_box()).draw(target, points, pen)
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+
+    use super::{blend, Pen, Polyline};
+    use crate::mock::Screen;
+
+    /* Off the pixel centres, so each line is 70% in one row (column) and 30% in the next */
+    #[test]
+    fn crossing_lines_keep_each_other() {
+        let pen = Pen { width: 1.0, color: Rgb565::BLACK, background: Rgb565::WHITE };
+        let mut screen = Screen::new(21, 21, Rgb565::WHITE);
+        let mut lines = Polyline::new(screen.bounding_box());
+        lines.draw_line(&mut screen, (2.0, 10.3), (18.0, 10.3), pen).unwrap();
+        lines.draw_line(&mut screen, (10.3, 2.0), (10.3, 18.0), pen).unwrap();
+
+        let core = blend(Rgb565::BLACK, Rgb565::WHITE, 22);
+        let fringe = blend(Rgb565::BLACK, Rgb565::WHITE, 10);
+        assert_eq!(screen.pixel(10, 10), core);
+        /* Neither line's faint fringe cuts into the other one's core */
+        for i in 2..=18 {
+            assert_eq!(screen.pixel(i, 10), core, "row at {i}");
+            assert_eq!(screen.pixel(10, i), core, "column at {i}");
+        }
+        assert_eq!(screen.pixel(11, 11), fringe);
+        assert_eq!(screen.pixel(11, 4), fringe);
+    }
+}
@@ -0,0 +1,44 @@
+/* Pseudo-random numbers for growing and seeding artworks: xorshift32, small and fast, and the
+   same sequence for the same seed on every chip. */
+
+pub struct Random(u32);
+
+impl Random {
+    /* Seeds go through splitmix32's mixer first: it's a bijection, so every seed starts its own
+       sequence and neighbouring ones don't start alike. Only the one seed mixed to zero, which
+       would stay zero, shares its start with another */
+    pub fn new(seed: u32) -> Self {
+        let mut z = seed.wrapping_add(0x9e37_79b9);
+        z = (z ^ (z >> 16)).wrapping_mul(0x85eb_ca6b);
+        z = (z ^ (z >> 13)).wrapping_mul(0xc2b2_ae35);
+        Random((z ^ (z >> 16)).max(1))
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    /* -1..1 */
+    pub fn signed(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1 << 23) as f32 - 1.0
+    }
+
+    /* 0..n, n > 0 */
+    pub fn below(&mut self, n: u32) -> u32 {
+        self.next_u32() % n
+    }
+
+    /* low..high */
+    pub fn between(&mut self, low: f32, high: f32) -> f32 {
+        low + (high - low) * (self.signed() + 1.0) / 2.0
+    }
+
+    pub fn chance(&mut self, p: f32) -> bool {
+        p > 0.0 && self.signed() < 2.0 * p - 1.0
+    }
+}
@@ -0,0 +1,10 @@
+/* Something that can be drawn again and again with the same result.
+
+   Renderers which can't keep a whole frame in memory draw a scene several times, each time
+   keeping only a part of it. */
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+
+pub trait Scene {
+    fn draw<D: DrawTarget<Color = Rgb565>>(&self, target: &mut D) -> Result<(), D::Error>;
+}
@@ -0,0 +1,695 @@
+/* Scenes written as text, for artworks described as data rather than code.
+
+   Every line is a stroke: a shape, then keys saying where it is and how it's drawn, `;`
+   comments to the end of the line:
+
+       rose at (35,180) r=30 n=6 d=71 k=60 brush=o lines=outline colour=#d81438
+
+   Shapes and their keys, in pixels of the 320x240 design screen and radians:
+
+       spiral    at (x,y), offset (x,y), growth=1 pixel per radian, start=0, clockwise,
+                 from=0, to. Different offsets along x and y give an oval one
+       segment   from (x,y), to (x,y), the parameter is the distance along it in pixels
+       rose      at (x,y), r, n. With d it's a Maurer rose, `steps=360` points 180·d/k
+                 degrees apart, k=180 unless given
+       stem      at (x,y), length. Grows up to (x,y) from `length` below, by `lsystem` and
+                 a bit differently every time the scene is loaded
+
+   and for all of them:
+
+       brush=.        FONT_10X20 glyph stamped along the stroke
+       density=1      samples per unit of the shape's parameter
+       spacing=1      pixels between the samples brushed, 0 brushes every one
+       lines=1        lines join every n-th sample, `outline` traces a rose's outline instead
+       colour=        foreground, #rrggbb, #rrggbb..#rrggbb blended, #rrggbb,#rrggbb,...
+                      spread evenly, or hue(from,to) in degrees around the wheel
+
+   The `=` may be left out. Numbers are sums of products of numbers, `pi`, `sqrt(...)` and
+   bracketed sums, written without spaces.
+
+   Nothing is allocated: each time the artwork is opened the scene is read into fixed size
+   lists and its stems grown into paths the caller lends, which the strokes then point into. */
+
+use core::f32::consts::PI;
+use core::fmt;
+use core::ops::Range;
+
+use embedded_graphics::pixelcolor::Rgb565;
+use heapless::Vec;
+use libm::sqrtf;
+
+use crate::artworks::{design, stem, Lines, Stroke, Tip, MAX_STROKES, PX};
+use crate::curve::{Curve, Maurer, Rose, Segment, Spiral};
+use crate::gradient::Gradient;
+use crate::lsystem::{Growth, Overflow, Path, Paths, MAX_PATHS};
+
+const MAX_COLOURS: usize = 32;
+
+/* Corners every half a degree */
+const OUTLINE_DENSITY: f32 = 360.0 / PI;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Problem {
+    UnknownShape,
+    UnknownKey,
+    NumberExpected,
+    WholeNumberExpected,
+    PointExpected,
+    ColourExpected,
+    BrushExpected,
+    /* Something straight after a value */
+    SpaceExpected,
+    Unmatched,
+    /* `lines=outline` on anything but a rose */
+    NoOutline,
+    Missing(&'static str),
+    TooBig,
+    /* A stem which doesn't fit where it's grown */
+    Overflow(Overflow),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::UnknownShape => write!(f, "unknown shape"),
+            Problem::UnknownKey => write!(f, "unknown key"),
+            Problem::NumberExpected => write!(f, "number expected"),
+            Problem::WholeNumberExpected => write!(f, "whole number expected"),
+            Problem::PointExpected => write!(f, "point expected"),
+            Problem::ColourExpected => write!(f, "colour expected"),
+            Problem::BrushExpected => write!(f, "brush expected"),
+            Problem::SpaceExpected => write!(f, "space expected"),
+            Problem::Unmatched => write!(f, "unmatched bracket"),
+            Problem::NoOutline => write!(f, "only roses have an outline"),
+            Problem::Missing(key) => write!(f, "`{}` missing", key),
+            Problem::TooBig => write!(f, "scene too big"),
+            Problem::Overflow(overflow) => write!(f, "stem: {}", overflow),
+        }
+    }
+}
+
+/* What went wrong, and where: line and column, both counted from 1 */
+#[derive(Clone, Copy, Debug)]
+pub struct Error {
+    pub problem: Problem,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}:{}", self.problem, self.line, self.column)
+    }
+}
+
+/* A problem and the byte offset it was found at */
+type Fault = (Problem, usize);
+
+/* Any shape a line can describe */
+enum Shape<'a> {
+    Spiral(Spiral),
+    Segment(Segment),
+    Rose(Rose),
+    Maurer(Maurer),
+    Path(Path<'a>),
+}
+
+impl Curve for Shape<'_> {
+    fn point(&self, t: f32) -> (f32, f32) {
+        match self {
+            Shape::Spiral(spiral) => spiral.point(t),
+            Shape::Segment(segment) => segment.point(t),
+            Shape::Rose(rose) => rose.point(t),
+            Shape::Maurer(maurer) => maurer.point(t),
+            Shape::Path(path) => path.point(t),
+        }
+    }
+
+    fn range(&self) -> core::ops::Range<f32> {
+        match self {
+            Shape::Spiral(spiral) => spiral.range(),
+            Shape::Segment(segment) => segment.range(),
+            Shape::Rose(rose) => rose.range(),
+            Shape::Maurer(maurer) => maurer.range(),
+            Shape::Path(path) => path.range(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Spiral,
+    Segment,
+    Rose,
+    Stem,
+}
+
+/* Keys of a line, in design pixels */
+#[derive(Default)]
+struct Keys {
+    at: Option<(f32, f32)>,
+    offset: Option<(f32, f32)>,
+    growth: Option<f32>,
+    start: Option<f32>,
+    clockwise: bool,
+    from: Option<f32>,
+    to: Option<f32>,
+    ends: [Option<(f32, f32)>; 2],
+    radius: Option<f32>,
+    n: Option<u32>,
+    d: Option<f32>,
+    k: Option<f32>,
+    steps: Option<u32>,
+    length: Option<f32>,
+}
+
+/* How a line's stroke is drawn */
+struct Look {
+    tip: Tip<'static>,
+    density: f32,
+    spacing: f32,
+    every: u32,
+    outline: bool,
+    color: Colour,
+}
+
+/* Palettes are kept as where their colours are among the scene's */
+enum Colour {
+    Gradient(Gradient<'static>),
+    Palette(Range<usize>),
+}
+
+/* Shapes of a scene and how they're drawn, for strokes to point into */
+pub struct Scene<'a> {
+    lines: Vec<(Shape<'a>, Look), MAX_STROKES>,
+    /* Of palettes */
+    colours: Vec<Rgb565, MAX_COLOURS>,
+}
+
+impl Scene<'_> {
+    pub fn strokes(&self) -> Vec<Stroke<'_>, MAX_STROKES> {
+        self.lines
+            .iter()
+            .map(|(shape, look)| Stroke {
+                tip: look.tip,
+                curve: shape,
+                density: look.density,
+                spacing: look.spacing,
+                lines: match shape {
+                    Shape::Maurer(maurer) if look.outline => Lines::Outline { curve: &maurer.rose, density: OUTLINE_DENSITY },
+                    _ if look.outline => Lines::Outline { curve: shape, density: OUTLINE_DENSITY },
+                    _ => Lines::Every(look.every),
+                },
+                color: match &look.color {
+                    Colour::Gradient(gradient) => *gradient,
+                    Colour::Palette(range) => Gradient::Palette(&self.colours[range.clone()]),
+                },
+            })
+            .collect()
+    }
+}
+
+/* Reads `scene`, all of it or nothing, growing its stems from `seed` into `paths` */
+pub fn load<'a, const N: usize>(scene: &str, seed: u32, paths: &'a mut Paths<N>) -> Result<Scene<'a>, Error> {
+    let mut parser = Parser {
+        source: scene,
+        position: 0,
+        colours: Vec::new(),
+        stems: Vec::new(),
+    };
+    let mut lines: Vec<_, MAX_STROKES> = Vec::new();
+    let result = loop {
+        match parser.line() {
+            Ok(None) => break Ok(()),
+            Ok(Some((shape, look, at))) => {
+                if lines.push((shape, look, at)).is_err() {
+                    break Err((Problem::TooBig, at));
+                }
+            }
+            Err(fault) => break Err(fault),
+        }
+    };
+    let result = result.and_then(|()| {
+        paths.grow(&parser.stems, seed).map_err(|(i, overflow)| {
+            /* Stems are grown in the order of their lines */
+            let stems = lines.iter().filter(|(shape, ..)| matches!(shape, Shape::Path(_)));
+            (Problem::Overflow(overflow), stems.map(|(.., at)| *at).nth(i).unwrap_or(0))
+        })
+    });
+    match result {
+        Ok(()) => {
+            let paths: &'a Paths<N> = paths;
+            /* Stems stand in for their paths in the order they were grown */
+            let mut grown = 0;
+            let lines = lines
+                .into_iter()
+                .map(|(shape, look, _)| match shape {
+                    Shape::Path(_) => {
+                        grown += 1;
+                        (Shape::Path(paths.path(grown - 1)), look)
+                    }
+                    shape => (shape, look),
+                })
+                .collect();
+            Ok(Scene { lines, colours: parser.colours })
+        }
+        Err((problem, at)) => {
+            let before = &scene[..at];
+            let start = before.rfind('\n').map_or(0, |newline| newline + 1);
+            Err(Error {
+                problem,
+                line: before.matches('\n').count() + 1,
+                column: before[start..].chars().count() + 1,
+            })
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+    /* Palette colours stored so far */
+    colours: Vec<Rgb565, MAX_COLOURS>,
+    /* Stems to grow once the scene is read */
+    stems: Vec<Growth, MAX_PATHS>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.position).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let eaten = self.peek() == Some(byte);
+        if eaten {
+            self.position += 1;
+        }
+        eaten
+    }
+
+    /* Spaces and a comment, up to the end of the line */
+    fn blank(&mut self) {
+        while let Some(b' ' | b'\t' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+        if self.peek() == Some(b';') {
+            while !matches!(self.peek(), None | Some(b'\n')) {
+                self.position += 1;
+            }
+        }
+    }
+
+    fn spaces(&mut self) {
+        while let Some(b' ' | b'\t') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn word(&mut self) -> &'a str {
+        let start = self.position;
+        while matches!(self.peek(), Some(byte) if byte.is_ascii_alphanumeric() || byte == b'_') {
+            self.position += 1;
+        }
+        &self.source[start..self.position]
+    }
+
+    /* The next stroke, with where its line starts, or none at the end */
+    fn line(&mut self) -> Result<Option<(Shape<'static>, Look, usize)>, Fault> {
+        loop {
+            self.blank();
+            match self.peek() {
+                None => return Ok(None),
+                Some(b'\n') => self.position += 1,
+                Some(_) => break,
+            }
+        }
+        let at = self.position;
+        let kind = match self.word() {
+            "spiral" => Kind::Spiral,
+            "segment" => Kind::Segment,
+            "rose" => Kind::Rose,
+            "stem" => Kind::Stem,
+            _ => return Err((Problem::UnknownShape, at)),
+        };
+
+        let mut keys = Keys::default();
+        let mut look = Look {
+            tip: Tip::Glyph('.'),
+            density: 1.0,
+            spacing: 1.0,
+            every: 1,
+            outline: false,
+            color: Colour::Gradient(Gradient::Foreground),
+        };
+        loop {
+            self.blank();
+            if matches!(self.peek(), None | Some(b'\n')) {
+                break;
+            }
+            let key_at = self.position;
+            match (kind, self.word()) {
+                (_, "brush") => look.tip = Tip::Glyph(self.brush()?),
+                (_, "density") => look.density = self.number()?,
+                (_, "spacing") => look.spacing = self.number()?,
+                (_, "lines") => {
+                    self.separator();
+                    let value_at = self.position;
+                    if self.word() == "outline" {
+                        if kind != Kind::Rose {
+                            return Err((Problem::NoOutline, value_at));
+                        }
+                        look.outline = true;
+                    } else {
+                        self.position = value_at;
+                        look.every = self.integer()?;
+                        look.outline = false;
+                    }
+                }
+                (_, "colour" | "color") => look.color = self.colour()?,
+                (Kind::Spiral | Kind::Rose | Kind::Stem, "at") => keys.at = Some(self.point()?),
+                (Kind::Spiral, "offset") => keys.offset = Some(self.point()?),
+                (Kind::Spiral, "growth") => keys.growth = Some(self.number()?),
+                (Kind::Spiral, "start") => keys.start = Some(self.number()?),
+                (Kind::Spiral, "clockwise") => keys.clockwise = true,
+                (Kind::Spiral, "from") => keys.from = Some(self.number()?),
+                (Kind::Spiral, "to") => keys.to = Some(self.number()?),
+                (Kind::Segment, "from") => keys.ends[0] = Some(self.point()?),
+                (Kind::Segment, "to") => keys.ends[1] = Some(self.point()?),
+                (Kind::Rose, "r") => keys.radius = Some(self.number()?),
+                (Kind::Rose, "n") => keys.n = Some(self.whole()?),
+                (Kind::Rose, "d") => keys.d = Some(self.number()?),
+                (Kind::Rose, "k") => keys.k = Some(self.number()?),
+                (Kind::Rose, "steps") => keys.steps = Some(self.whole()?),
+                (Kind::Stem, "length") => keys.length = Some(self.number()?),
+                _ => return Err((Problem::UnknownKey, key_at)),
+            }
+            if !matches!(self.peek(), None | Some(b' ' | b'\t' | b'\r' | b'\n' | b';')) {
+                return Err((Problem::SpaceExpected, self.position));
+            }
+        }
+
+        let missing = |key| (Problem::Missing(key), at);
+        let centre = |at: Option<(f32, f32)>| at.map(|(x, y)| design(x, y)).ok_or(missing("at"));
+        let shape = match kind {
+            Kind::Spiral => Shape::Spiral(Spiral {
+                centre: centre(keys.at)?,
+                offset: keys.offset.map_or((0.0, 0.0), |(x, y)| design(x, y)),
+                growth: keys.growth.unwrap_or(1.0) * PX,
+                start: keys.start.unwrap_or(0.0),
+                clockwise: keys.clockwise,
+                range: keys.from.unwrap_or(0.0)..keys.to.ok_or(missing("to"))?,
+            }),
+            Kind::Segment => {
+                let [from, to] = keys.ends;
+                let (from, to) = (from.ok_or(missing("from"))?, to.ok_or(missing("to"))?);
+                /* The parameter is in frame units, densities are per design pixel */
+                look.density /= PX;
+                Shape::Segment(Segment { from: design(from.0, from.1), to: design(to.0, to.1) })
+            }
+            Kind::Rose => {
+                let rose = Rose {
+                    centre: centre(keys.at)?,
+                    radius: keys.radius.ok_or(missing("r"))? * PX,
+                    petals: keys.n.ok_or(missing("n"))?,
+                };
+                match keys.d {
+                    Some(d) => Shape::Maurer(Maurer {
+                        rose,
+                        degrees: keys.k.map_or(d, |k| 180.0 * d / k),
+                        steps: keys.steps.unwrap_or(360),
+                    }),
+                    None => Shape::Rose(rose),
+                }
+            }
+            Kind::Stem => {
+                let (x, y) = keys.at.ok_or(missing("at"))?;
+                let growth = stem(x, y, keys.length.ok_or(missing("length"))?);
+                self.stems.push(growth).map_err(|_| (Problem::TooBig, at))?;
+                Shape::Path(Path::NONE)
+            }
+        };
+        Ok(Some((shape, look, at)))
+    }
+
+    /* `=`, or spaces as in `at (x,y)` */
+    fn separator(&mut self) {
+        if !self.eat(b'=') {
+            self.spaces();
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, Fault> {
+        self.separator();
+        self.expression()
+    }
+
+    fn whole(&mut self) -> Result<u32, Fault> {
+        self.separator();
+        self.integer()
+    }
+
+    fn expression(&mut self) -> Result<f32, Fault> {
+        let at = self.position;
+        let value = self.sum()?;
+        if !value.is_finite() {
+            return Err((Problem::NumberExpected, at));
+        }
+        Ok(value)
+    }
+
+    fn integer(&mut self) -> Result<u32, Fault> {
+        let at = self.position;
+        let value = self.expression()?;
+        if !(0.0..16_777_216.0).contains(&value) || value as u32 as f32 != value {
+            return Err((Problem::WholeNumberExpected, at));
+        }
+        Ok(value as u32)
+    }
+
+    fn sum(&mut self) -> Result<f32, Fault> {
+        let mut value = self.product()?;
+        loop {
+            if self.eat(b'+') {
+                value += self.product()?;
+            } else if self.eat(b'-') {
+                value -= self.product()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<f32, Fault> {
+        let mut value = self.factor()?;
+        loop {
+            if self.eat(b'*') {
+                value *= self.factor()?;
+            } else if self.eat(b'/') {
+                value /= self.factor()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<f32, Fault> {
+        let at = self.position;
+        if self.eat(b'-') {
+            return Ok(-self.factor()?);
+        }
+        if self.eat(b'(') {
+            return self.bracketed(at);
+        }
+        if matches!(self.peek(), Some(byte) if byte.is_ascii_digit() || byte == b'.') {
+            while matches!(self.peek(), Some(byte) if byte.is_ascii_digit() || byte == b'.') {
+                self.position += 1;
+            }
+            return self.source[at..self.position].parse().map_err(|_| (Problem::NumberExpected, at));
+        }
+        match self.word() {
+            "pi" => Ok(PI),
+            "sqrt" if self.eat(b'(') => Ok(sqrtf(self.bracketed(at)?)),
+            _ => Err((Problem::NumberExpected, at)),
+        }
+    }
+
+    /* A sum after `(` found at `opened`, and the `)` */
+    fn bracketed(&mut self, opened: usize) -> Result<f32, Fault> {
+        let value = self.sum()?;
+        if !self.eat(b')') {
+            return Err((Problem::Unmatched, opened));
+        }
+        Ok(value)
+    }
+
+    /* (x,y), spaces allowed inside */
+    fn pair(&mut self, problem: Problem) -> Result<(f32, f32), Fault> {
+        let expect = |parser: &mut Self, byte| {
+            parser.spaces();
+            let at = parser.position;
+            if !parser.eat(byte) {
+                return Err((problem, at));
+            }
+            Ok(())
+        };
+        expect(self, b'(')?;
+        self.spaces();
+        let x = self.sum()?;
+        expect(self, b',')?;
+        self.spaces();
+        let y = self.sum()?;
+        expect(self, b')')?;
+        Ok((x, y))
+    }
+
+    fn point(&mut self) -> Result<(f32, f32), Fault> {
+        self.separator();
+        self.pair(Problem::PointExpected)
+    }
+
+    fn brush(&mut self) -> Result<char, Fault> {
+        self.separator();
+        match self.source[self.position..].chars().next() {
+            Some(glyph) if !glyph.is_whitespace() => {
+                self.position += glyph.len_utf8();
+                Ok(glyph)
+            }
+            _ => Err((Problem::BrushExpected, self.position)),
+        }
+    }
+
+    fn colour(&mut self) -> Result<Colour, Fault> {
+        self.separator();
+        let at = self.position;
+        match self.word() {
+            "foreground" => return Ok(Colour::Gradient(Gradient::Foreground)),
+            "hue" => {
+                let hue_at = self.position;
+                let (from, to) = self.pair(Problem::NumberExpected)?;
+                if !(0.0..=65535.0).contains(&from) || !(0.0..=65535.0).contains(&to) {
+                    return Err((Problem::WholeNumberExpected, hue_at));
+                }
+                return Ok(Colour::Gradient(Gradient::Hue { from: from as u16, to: to as u16 }));
+            }
+            "" => {}
+            _ => return Err((Problem::ColourExpected, at)),
+        }
+        let first = self.hex()?;
+        if self.eat(b'.') {
+            if !self.eat(b'.') {
+                return Err((Problem::ColourExpected, self.position));
+            }
+            return Ok(Colour::Gradient(Gradient::TwoStop(first, self.hex()?)));
+        }
+        if self.peek() != Some(b',') {
+            return Ok(Colour::Gradient(Gradient::Solid(first)));
+        }
+        let start = self.colours.len();
+        self.store(first, at)?;
+        while self.eat(b',') {
+            let at = self.position;
+            let colour = self.hex()?;
+            self.store(colour, at)?;
+        }
+        Ok(Colour::Palette(start..self.colours.len()))
+    }
+
+    /* #rrggbb */
+    fn hex(&mut self) -> Result<Rgb565, Fault> {
+        let at = self.position;
+        let digits = self.source.get(at + 1..at + 7).filter(|digits| digits.bytes().all(|byte| byte.is_ascii_hexdigit()));
+        let (Some(b'#'), Some(digits)) = (self.peek(), digits) else {
+            return Err((Problem::ColourExpected, at));
+        };
+        let rgb = u32::from_str_radix(digits, 16).map_err(|_| (Problem::ColourExpected, at))?;
+        self.position += 7;
+        Ok(Rgb565::new((rgb >> 19) as u8 & 0x1f, (rgb >> 10) as u8 & 0x3f, (rgb >> 3) as u8 & 0x1f))
+    }
+
+    fn store(&mut self, colour: Rgb565, at: usize) -> Result<(), Fault> {
+        self.colours.push(colour).map_err(|_| (Problem::TooBig, at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::ToString;
+
+    use super::{load, Problem};
+    use crate::artworks::{EYE_SCENE, GARDEN_SCENE, LOLLIPOP_GUY_SCENE, PATH_VERTICES};
+    use crate::lsystem::{Overflow, Paths};
+
+    /* What's wrong with `scene`, and its line and column */
+    fn fault(scene: &str) -> (Problem, usize, usize) {
+        let mut paths: Paths<PATH_VERTICES> = Paths::new();
+        let error = load(scene, 1, &mut paths).err().unwrap();
+        (error.problem, error.line, error.column)
+    }
+
+    #[test]
+    fn shipped_scenes_parse() {
+        let mut paths: Paths<PATH_VERTICES> = Paths::new();
+        for scene in [EYE_SCENE, LOLLIPOP_GUY_SCENE, GARDEN_SCENE] {
+            let strokes = scene.lines().filter(|line| !line.trim().is_empty() && !line.starts_with(';')).count();
+            for seed in 0..16 {
+                match load(scene, seed, &mut paths) {
+                    Ok(loaded) => assert_eq!(loaded.strokes().len(), strokes),
+                    Err(error) => panic!("{}", error),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_words_are_placed() {
+        assert_eq!(fault("; Flowers\nflower at (1,2)"), (Problem::UnknownShape, 2, 1));
+        assert_eq!(fault("rose at (1,2) r=3 n=4\nrose at (1,2) r=3 petals=4"), (Problem::UnknownKey, 2, 19));
+        /* Keys of other shapes too */
+        assert_eq!(fault("segment from (0,0) to (1,1) at (2,2)"), (Problem::UnknownKey, 1, 29));
+    }
+
+    #[test]
+    fn bad_numbers_are_placed() {
+        assert_eq!(fault("spiral at (1,2) to=1\nspiral at (1,2) to=ten"), (Problem::NumberExpected, 2, 20));
+        assert_eq!(fault("spiral at (1,2) to=3x"), (Problem::SpaceExpected, 1, 21));
+        assert_eq!(fault("spiral at (1,2) to=2*(pi+1"), (Problem::Unmatched, 1, 22));
+        assert_eq!(fault("spiral at (1,2) to=1/0"), (Problem::NumberExpected, 1, 20));
+        assert_eq!(fault("rose at (1,2) r=3 n=2.5"), (Problem::WholeNumberExpected, 1, 21));
+        assert_eq!(fault("rose at (1, x) r=3 n=2"), (Problem::NumberExpected, 1, 13));
+    }
+
+    #[test]
+    fn missing_keys_are_placed_at_their_line() {
+        assert_eq!(fault("rose at (1,2) r=3 n=4\n  rose r=3 n=4"), (Problem::Missing("at"), 2, 3));
+        assert_eq!(fault("stem length=50"), (Problem::Missing("at"), 1, 1));
+        assert_eq!(fault("rose at 1,2 r=3 n=4"), (Problem::PointExpected, 1, 9));
+        assert_eq!(fault("spiral at (1,2)"), (Problem::Missing("to"), 1, 1));
+    }
+
+    /* Where the colour after `..` or `,` should have been */
+    #[test]
+    fn unterminated_colour_ranges_are_placed() {
+        assert_eq!(fault("rose at (1,2) r=3 n=4 colour=#ff0000.."), (Problem::ColourExpected, 1, 39));
+        assert_eq!(fault("rose at (1,2) r=3 n=4 colour=#ff0000..#00ff"), (Problem::ColourExpected, 1, 39));
+        assert_eq!(fault("rose at (1,2) r=3 n=4 colour=#ff0000.#00ff00"), (Problem::ColourExpected, 1, 38));
+        assert_eq!(fault("rose at (1,2) r=3 n=4 colour=#ff0000,"), (Problem::ColourExpected, 1, 38));
+    }
+
+    #[test]
+    fn errors_read_as_line_and_column() {
+        let mut paths: Paths<PATH_VERTICES> = Paths::new();
+        let error = load("\nrose at (1,2) r=3 n=4 petals=4", 1, &mut paths).err().unwrap();
+        assert_eq!(error.to_string(), "unknown key at 2:23");
+    }
+
+    /* A stem which doesn't fit is reported on its own line */
+    #[test]
+    fn stem_overflow_is_placed() {
+        let mut paths: Paths<40> = Paths::new();
+        let scene = "stem at (40,200) length=60\n; Doesn't fit next to the first one\nstem at (80,200) length=60\n";
+        let error = load(scene, 1, &mut paths).err().unwrap();
+        assert_eq!((error.problem, error.line, error.column), (Problem::Overflow(Overflow::Vertices), 3, 1));
+    }
+}
@@ -0,0 +1,364 @@
+/* String table for everything shown in menus and captions */
+
+use embedded_graphics::mono_font::MonoFont;
+
+use heapless::String;
+
+pub const LANGUAGES: usize = 2;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Language {
+    English,
+    Czech,
+}
+
+impl Language {
+    pub fn next(self) -> Self {
+        match self {
+            Language::English => Language::Czech,
+            Language::Czech => Language::English,
+        }
+    }
+
+    pub fn name(self) -> Str {
+        match self {
+            Language::English => Str::English,
+            Language::Czech => Str::Czech,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Str {
+    Eye,
+    EyeDescription,
+    LollipopGuy,
+    LollipopGuyDescription,
+    Garden,
+    GardenDescription,
+    TurtleFlower,
+    TurtleFlowerDescription,
+    TurningSquares,
+    TurningSquaresDescription,
+    IceCrystal,
+    IceCrystalDescription,
+    Roses,
+    RosesDescription,
+    Rose,
+    Hypotrochoid,
+    HypotrochoidDescription,
+    Lace,
+    LaceDescription,
+    Epitrochoid,
+    EpitrochoidDescription,
+    FractalPlant,
+    FractalPlantDescription,
+    KochSnowflake,
+    KochSnowflakeDescription,
+    Dragon,
+    DragonDescription,
+    Spirograph,
+    SpirographDescription,
+    Ring,
+    Wheel,
+    Pen,
+    Inside,
+    Outside,
+    Fractals,
+    FractalsDescription,
+    ZoomOut,
+    Julia,
+    Mandelbrot,
+    Iterations,
+    Life,
+    LifeDescription,
+    Wireframes,
+    WireframesDescription,
+    Cube,
+    Icosahedron,
+    Torus,
+    Axis,
+    Meadow,
+    MeadowDescription,
+    Seed,
+    Lissajous,
+    LissajousDescription,
+    Ratio,
+    Phase,
+    Drift,
+    Amplitude,
+    Save,
+    Saved,
+    AdaptedBy,
+    Original,
+    Settings,
+    Language,
+    English,
+    Czech,
+    StatusBar,
+    Animation,
+    Lines,
+    Theme,
+    Light,
+    Dark,
+    HighContrast,
+    On,
+    Off,
+    Back,
+}
+
+impl Str {
+    /* One column per `Language`, in declaration order */
+    fn row(self) -> [&'static str; LANGUAGES] {
+        match self {
+            Str::Eye => ["Eye", "Oko"],
+            Str::EyeDescription => [
+                "Archimedean spiral x = (v − 50)·sin(v), y = (v − 50)·cos(v), v = i / 20π, 8000 samples.\n\
+                 Lashes: the last 5200 samples use x = (v + 20)·sin(v), which pulls the spiral apart.",
+                "Archimédova spirála x = (v − 50)·sin(v), y = (v − 50)·cos(v), v = i / 20π, 8000 bodů.\n\
+                 Řasy: posledních 5200 bodů používá x = (v + 20)·sin(v), což spirálu roztáhne.",
+            ],
+            Str::LollipopGuy => ["Lollipop Guy", "Kluk s lízátkem"],
+            Str::LollipopGuyDescription => [
+                "Body, head, eyes and lollipop are Archimedean spirals r = v ± c with v = i / (k·π), \
+                 k = 40, 60, 20 and 30.\n\
+                 The hand is the line y = x from 125 to 175.",
+                "Tělo, hlava, oči a lízátko jsou Archimédovy spirály r = v ± c, v = i / (k·π), \
+                 k = 40, 60, 20 a 30.\n\
+                 Ruka je přímka y = x od 125 do 175.",
+            ],
+            Str::Garden => ["Garden", "Zahrada"],
+            Str::GardenDescription => [
+                "Maurer roses r = 30·sin(n·a), a = t·d·π / k on L-system stems A → F[+G−G]F[−G+G]A, leaves a little different every time.\n\
+                 (n, d) = (6, 71), (7, 19), (2, 39), (8, 27), (5, 97); the big one is r = 80·sin(6·a), d = 71.",
+                "Maurerovy růže r = 30·sin(n·a), a = t·d·π / k na stoncích z L-systému A → F[+G−G]F[−G+G]A, listy pokaždé trochu jiné.\n\
+                 (n, d) = (6, 71), (7, 19), (2, 39), (8, 27), (5, 97); velká růže je r = 80·sin(6·a), d = 71.",
+            ],
+            Str::TurtleFlower => ["Turtle flower", "Želví květina"],
+            Str::TurtleFlowerDescription => [
+                "Turtle script: a petal is two arcs, repeat 30 [forward 3 right 3], with a right turn of 90 after each, drawn 12 times 30 degrees apart.\n\
+                 The stamens go out and back 36 times: forward 14 back 14 right 10.",
+                "Želví skript: okvětní lístek jsou dva oblouky, repeat 30 [forward 3 right 3], po každém otočka o 90 vpravo, nakreslený 12krát po 30 stupních.\n\
+                 Tyčinky jdou 36krát tam a zpět: forward 14 back 14 right 10.",
+            ],
+            Str::TurningSquares => ["Turning squares", "Otáčené čtverce"],
+            Str::TurningSquaresDescription => [
+                "Turtle script: a square, repeat 4 [forward 80 right 90], drawn 36 times, each turned 10 degrees further, in three colours.",
+                "Želví skript: čtverec, repeat 4 [forward 80 right 90], nakreslený 36krát, pokaždé otočený o dalších 10 stupňů, ve třech barvách.",
+            ],
+            Str::IceCrystal => ["Ice crystal", "Ledový krystal"],
+            Str::IceCrystalDescription => [
+                "Turtle script: six arms 60 degrees apart, each with three pairs of twigs at 45 degrees. The turtle walks back to the middle with its pen up.",
+                "Želví skript: šest ramen po 60 stupních, každé se třemi páry větviček pod 45 stupni. Zpět doprostřed se želva vrací se zvednutým perem.",
+            ],
+            Str::Roses => ["Rose explorer", "Průzkumník růží"],
+            Str::RosesDescription => [
+                "Maurer rose: 361 points of the rose r = sin(n·θ), θ = k·d degrees, joined by lines.\n\
+                 up/down change n or d, ok picks which one or \"save\", which adds the rose to the menu.",
+                "Maurerova růže: 361 bodů růže r = sin(n·θ), θ = k·d stupňů, spojených čarami.\n\
+                 nahoru/dolů mění n nebo d, ok vybírá které z nich nebo \"uložit\", což růži přidá do menu.",
+            ],
+            Str::Rose => ["Rose", "Růže"],
+            Str::Hypotrochoid => ["Hypotrochoid", "Hypotrochoida"],
+            Str::HypotrochoidDescription => [
+                "A wheel of 36 teeth rolling inside a ring of 105, the pen 30 teeth from its centre.\n\
+                 x = 69·cos(t) + 30·cos(69/36·t), y = 69·sin(t) − 30·sin(69/36·t); gcd(105, 36) = 3, so it closes after 36/3 = 12 turns.",
+                "Kolečko s 36 zuby se valí uvnitř kruhu se 105 zuby, pero 30 zubů od jeho středu.\n\
+                 x = 69·cos(t) + 30·cos(69/36·t), y = 69·sin(t) − 30·sin(69/36·t); gcd(105, 36) = 3, takže se uzavře po 36/3 = 12 obězích.",
+            ],
+            Str::Lace => ["Lace", "Krajka"],
+            Str::LaceDescription => [
+                "Hypotrochoid of a 56 tooth wheel inside a 96 tooth ring, the pen almost at its rim.\n\
+                 x = 40·cos(t) + 52·cos(40/56·t), y = 40·sin(t) − 52·sin(40/56·t); gcd(96, 56) = 8: 7 turns, 12 loops.",
+                "Hypotrochoida kolečka s 56 zuby uvnitř kruhu s 96 zuby, pero skoro na jeho okraji.\n\
+                 x = 40·cos(t) + 52·cos(40/56·t), y = 40·sin(t) − 52·sin(40/56·t); gcd(96, 56) = 8: 7 oběhů, 12 smyček.",
+            ],
+            Str::Epitrochoid => ["Epitrochoid", "Epitrochoida"],
+            Str::EpitrochoidDescription => [
+                "A wheel of 21 teeth rolling around the outside of a ring of 60, the pen 30 teeth out.\n\
+                 x = 81·cos(t) − 30·cos(81/21·t), y = 81·sin(t) − 30·sin(81/21·t); gcd(60, 21) = 3: 7 turns.",
+                "Kolečko s 21 zuby se valí po vnější straně kruhu se 60 zuby, pero 30 zubů od středu.\n\
+                 x = 81·cos(t) − 30·cos(81/21·t), y = 81·sin(t) − 30·sin(81/21·t); gcd(60, 21) = 3: 7 oběhů.",
+            ],
+            Str::FractalPlant => ["Fractal plant", "Fraktální rostlina"],
+            Str::FractalPlantDescription => [
+                "L-system X → F+[[X]−X]−F[−FX]+X, F → FF rewritten 4 times and drawn by a turtle: F steps forward, +/− turn by 25°, [ ] branch off.\n\
+                 Branches bend and shorten a little at random, so it grows differently every time.",
+                "L-systém X → F+[[X]−X]−F[−FX]+X, F → FF přepsaný 4krát a nakreslený želvou: F krok vpřed, +/− otočka o 25°, [ ] odbočka.\n\
+                 Větve se náhodně trochu ohýbají a zkracují, takže pokaždé vyroste jinak.",
+            ],
+            Str::KochSnowflake => ["Koch snowflake", "Kochova vločka"],
+            Str::KochSnowflakeDescription => [
+                "L-system F−−F−−F, F → F+F−−F+F, 60° turns, rewritten 4 times.\n\
+                 Every side becomes 4 sides a third as long, 768 in the end.",
+                "L-systém F−−F−−F, F → F+F−−F+F, otočky o 60°, přepsaný 4krát.\n\
+                 Každá strana se změní ve 4 třetinové, nakonec jich je 768.",
+            ],
+            Str::Dragon => ["Dragon curve", "Dračí křivka"],
+            Str::DragonDescription => [
+                "L-system FX, X → X+YF+, Y → −FX−Y, 90° turns, rewritten 10 times: 1024 steps.\n\
+                 The same as folding a strip of paper in half 10 times and opening every fold to a right angle.",
+                "L-systém FX, X → X+YF+, Y → −FX−Y, otočky o 90°, přepsaný 10krát: 1024 kroků.\n\
+                 Totéž jako 10krát přeložit proužek papíru napůl a každý přehyb rozevřít do pravého úhlu.",
+            ],
+            Str::Spirograph => ["Spirograph", "Spirograf"],
+            Str::SpirographDescription => [
+                "Pick a ring, a wheel, where the pen goes and which side of the ring the wheel rolls on.\n\
+                 The curve is traced until it closes, after wheel/gcd(ring, wheel) turns around the ring.",
+                "Vyberte kruh, kolečko, kam přijde pero a po které straně kruhu se kolečko valí.\n\
+                 Křivka se kreslí, dokud se neuzavře, po kolečko/gcd(kruh, kolečko) obězích kolem kruhu.",
+            ],
+            Str::Ring => ["ring", "kruh"],
+            Str::Wheel => ["wheel", "kolečko"],
+            Str::Pen => ["pen", "pero"],
+            Str::Inside => ["inside", "uvnitř"],
+            Str::Outside => ["outside", "vně"],
+            Str::Fractals => ["Fractals", "Fraktály"],
+            Str::FractalsDescription => [
+                "Points c for which z ↦ z² + c, starting at 0, stays within |z| ≤ 2 form the Mandelbrot set; the Julia set of c starts z at each point instead.\n\
+                 Colours count the steps until z leaves, black points stay. up/down move the crosshair, ok zooms in 3x.",
+                "Body c, pro které z ↦ z² + c od 0 zůstává v |z| ≤ 2, tvoří Mandelbrotovu množinu; Juliova množina c začíná z v každém bodě.\n\
+                 Barvy počítají kroky, než z uteče, černé body zůstávají. nahoru/dolů posouvá kříž, ok přibližuje 3x.",
+            ],
+            Str::ZoomOut => ["zoom out", "oddálit"],
+            Str::Julia => ["Julia", "Julia"],
+            Str::Mandelbrot => ["Mandelbrot", "Mandelbrot"],
+            Str::Iterations => ["iterations", "iterace"],
+            Str::Life => ["Game of Life", "Hra života"],
+            Str::LifeDescription => [
+                "John Conway's cellular automaton: a cell with 3 live neighbours comes alive, one with 2 or 3 stays alive, all others die.\n\
+                 ok pauses; running, up/down change the speed; paused, up steps and down fills anew: random, glider gun, pulsar.",
+                "Buněčný automat Johna Conwaye: buňka se 3 živými sousedy ožije, se 2 nebo 3 zůstane živá, ostatní umírají.\n\
+                 ok pozastaví; za běhu nahoru/dolů mění rychlost; při pauze nahoru krokuje a dolů plní znovu: náhodně, kanón na kluzáky, pulsar.",
+            ],
+            Str::Wireframes => ["3D wireframes", "3D drátěné modely"],
+            Str::WireframesDescription => [
+                "Vertices rotated by the matrices Rz·Ry·Rx and projected in perspective, x' = x·d / (d + z), d = 4, joined by their edges. Further edges are fainter.\n\
+                 Each frame erases the lines of the one before. up/down change the model, ok the axis it spins around.",
+                "Vrcholy otočené maticemi Rz·Ry·Rx a promítnuté perspektivně, x' = x·d / (d + z), d = 4, spojené hranami. Vzdálenější hrany jsou bledší.\n\
+                 Každý snímek smaže čáry předchozího. nahoru/dolů mění model, ok osu, kolem které se točí.",
+            ],
+            Str::Cube => ["cube", "krychle"],
+            Str::Icosahedron => ["icosahedron", "dvacetistěn"],
+            Str::Torus => ["torus", "anuloid"],
+            Str::Axis => ["axis", "osa"],
+            Str::Meadow => ["Wild garden", "Divoká zahrada"],
+            Str::MeadowDescription => [
+                "Maurer roses on L-system stems, how many, where, how tall, with how many petals and in what colours all picked by a seed from the chip's random number generator.\n\
+                 up grows another garden, down the one before; ok enters a seed digit by digit to grow that garden again.",
+                "Maurerovy růže na stoncích z L-systému; kolik jich je, kde, jak vysoké, s kolika okvětními lístky a v jakých barvách určí semínko z generátoru náhodných čísel čipu.\n\
+                 nahoru vypěstuje další zahradu, dolů tu předchozí; ok zadá semínko číslici po číslici a vypěstuje tu zahradu znovu.",
+            ],
+            Str::Seed => ["seed", "semínko"],
+            Str::Lissajous => ["Lissajous", "Lissajous"],
+            Str::LissajousDescription => [
+                "Lissajous figure x = sin(a·t + φ), y = sin(b·t), as an oscilloscope draws it in XY mode.\n\
+                 The phase φ drifts on its own; up/down change the ratio a:b, φ, the drift or the size, ok picks which.",
+                "Lissajousův obrazec x = sin(a·t + φ), y = sin(b·t), jak ho kreslí osciloskop v režimu XY.\n\
+                 Fáze φ se sama posouvá; nahoru/dolů mění poměr a:b, φ, posun nebo velikost, ok vybírá co.",
+            ],
+            Str::Ratio => ["ratio", "poměr"],
+            Str::Phase => ["phase", "fáze"],
+            Str::Drift => ["drift", "posun"],
+            Str::Amplitude => ["size", "velikost"],
+            Str::Save => ["save", "uložit"],
+            Str::Saved => ["saved", "uloženo"],
+            Str::AdaptedBy => [
+                "Samuel Benko, adapted by Kirill Mikhailov",
+                "Samuel Benko, upravil Kirill Mikhailov",
+            ],
+            Str::Original => ["Made for esp-gallery", "Vytvořeno pro esp-gallery"],
+            Str::Settings => ["Settings", "Nastavení"],
+            Str::Language => ["Language", "Jazyk"],
+            Str::English => ["English", "English"],
+            Str::Czech => ["Čeština", "Čeština"],
+            Str::StatusBar => ["Status bar", "Stavový řádek"],
+            Str::Animation => ["Animation", "Animace"],
+            Str::Lines => ["Lines", "Čáry"],
+            Str::Theme => ["Theme", "Motiv"],
+            Str::Light => ["light", "světlý"],
+            Str::Dark => ["dark", "tmavý"],
+            Str::HighContrast => ["high contrast", "kontrastní"],
+            Str::On => ["on", "zap."],
+            Str::Off => ["off", "vyp."],
+            Str::Back => ["Back", "Zpět"],
+        }
+    }
+
+    pub fn text(self, language: Language) -> &'static str {
+        self.row()[language as usize]
+    }
+}
+
+/* Closest ASCII spelling of characters which fonts commonly lack */
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' => "a",
+        'Á' | 'À' | 'Â' | 'Ä' | 'Ã' | 'Å' => "A",
+        'č' | 'ç' => "c",
+        'Č' | 'Ç' => "C",
+        'ď' => "d",
+        'Ď' => "D",
+        'é' | 'ě' | 'è' | 'ê' | 'ë' => "e",
+        'É' | 'Ě' | 'È' | 'Ê' | 'Ë' => "E",
+        'í' | 'ì' | 'î' | 'ï' => "i",
+        'Í' | 'Ì' | 'Î' | 'Ï' => "I",
+        'ľ' | 'ĺ' => "l",
+        'Ľ' | 'Ĺ' => "L",
+        'ň' | 'ñ' => "n",
+        'Ň' | 'Ñ' => "N",
+        'ó' | 'ò' | 'ô' | 'ö' | 'õ' => "o",
+        'Ó' | 'Ò' | 'Ô' | 'Ö' | 'Õ' => "O",
+        'ř' | 'ŕ' => "r",
+        'Ř' | 'Ŕ' => "R",
+        'š' => "s",
+        'Š' => "S",
+        'ť' => "t",
+        'Ť' => "T",
+        'ú' | 'ů' | 'ù' | 'û' | 'ü' => "u",
+        'Ú' | 'Ů' | 'Ù' | 'Û' | 'Ü' => "U",
+        'ý' | 'ÿ' => "y",
+        'Ý' => "Y",
+        'ž' => "z",
+        'Ž' => "Z",
+        'ß' => "ss",
+        'π' => "pi",
+        'θ' => "theta",
+        'φ' => "phi",
+        '·' | '×' => "*",
+        '−' | '–' | '—' => "-",
+        '±' => "+-",
+        '…' => "...",
+        '→' | '↦' => "->",
+        '²' => "^2",
+        '“' | '”' | '„' => "\"",
+        '‘' | '’' => "'",
+        _ => return None,
+    })
+}
+
+/* Fonts draw a replacement glyph for characters they don't have, which is what an
+   unmapped character resolves to. ASCII is always assumed to be present. */
+pub fn has_glyph(font: &MonoFont, c: char) -> bool {
+    c.is_ascii() || font.glyph_mapping.index(c) != font.glyph_mapping.index('\u{FFFF}')
+}
+
+/* Copy of `text` which `font` can draw: missing characters are transliterated or replaced by '?' */
+pub fn printable<const N: usize>(font: &MonoFont, text: &str) -> String<N> {
+    let mut out = String::new();
+    for c in text.chars() {
+        let pushed = if has_glyph(font, c) {
+            out.push(c)
+        } else {
+            match transliterate(c) {
+                Some(replacement) => out.push_str(replacement),
+                None => out.push('?'),
+            }
+        };
+        /* Out of room: keep what fits */
+        if pushed.is_err() {
+            break;
+        }
+    }
+    out
+}
@@ -0,0 +1,99 @@
+/* Banded renderer for chips which can't hold a whole frame.
+
+   The scene is drawn once per horizontal band into a small strip buffer; everything outside the
+   band is thrown away and the finished band goes to the display in a single burst. Every pixel
+   is written once with its final colour, so there is no flicker and no progressive drawing. */
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+};
+
+use crate::scene::Scene;
+
+/* Draw target covering the whole `screen`, which only keeps pixels inside `band` */
+pub struct Strip<'a> {
+    pixels: &'a mut [Rgb565],
+    screen: Rectangle,
+    band: Rectangle,
+}
+
+impl<'a> Strip<'a> {
+    fn index(&self, point: Point) -> usize {
+        let offset = point - self.band.top_left;
+        offset.y as usize * self.band.size.width as usize + offset.x as usize
+    }
+}
+
+impl Dimensions for Strip<'_> {
+    fn bounding_box(&self) -> Rectangle {
+        self.screen
+    }
+}
+
+impl DrawTarget for Strip<'_> {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if self.band.contains(point) {
+                let index = self.index(point);
+                self.pixels[index] = color;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.band);
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        for y in area.rows() {
+            let start = self.index(Point::new(area.top_left.x, y));
+            self.pixels[start..start + area.size.width as usize].fill(color);
+        }
+        Ok(())
+    }
+}
+
+/* Draws `scene` into `area` of `display` band by band. `buffer` holds one band: its length
+   divided by the width of `area` is the band height */
+pub fn render_banded<S: Scene, D: DrawTarget<Color = Rgb565>>(
+    scene: &S,
+    display: &mut D,
+    area: Rectangle,
+    buffer: &mut [Rgb565],
+    background: Rgb565,
+) -> Result<(), D::Error> {
+    let width = area.size.width;
+    if width == 0 {
+        return Ok(());
+    }
+    let band_height = (buffer.len() as u32 / width).max(1);
+
+    let mut top = 0;
+    while top < area.size.height {
+        let height = band_height.min(area.size.height - top);
+        let band = Rectangle::new(area.top_left + Size::new(0, top), Size::new(width, height));
+        let pixels = &mut buffer[..(width * height) as usize];
+        pixels.fill(background);
+
+        let mut strip = Strip {
+            pixels,
+            screen: area,
+            band,
+        };
+        scene.draw(&mut strip).map_err(|never| match never {})?;
+
+        display.fill_contiguous(&band, strip.pixels.iter().copied())?;
+        top += height;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+/* Colours of menus, panels and the strokes artworks leave to the theme */
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+
+use crate::strings::Str;
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub name: Str,
+    pub background: Rgb565,
+    /* Text and uncoloured strokes */
+    pub foreground: Rgb565,
+    /* Status bar, caption border and title */
+    pub accent: Rgb565,
+    /* Menu pointer */
+    pub selection: Rgb565,
+    /* Caption panel */
+    pub caption: Rgb565,
+}
+
+pub static THEMES: [Theme; 3] = [
+    /* What the gallery always looked like */
+    Theme {
+        name: Str::Light,
+        background: Rgb565::WHITE,
+        foreground: Rgb565::BLACK,
+        accent: Rgb565::BLACK,
+        selection: Rgb565::BLACK,
+        caption: Rgb565::WHITE,
+    },
+    Theme {
+        name: Str::Dark,
+        background: Rgb565::BLACK,
+        foreground: Rgb565::new(26, 52, 26),
+        accent: Rgb565::new(4, 44, 26),
+        selection: Rgb565::new(31, 40, 0),
+        caption: Rgb565::new(3, 6, 4),
+    },
+    Theme {
+        name: Str::HighContrast,
+        background: Rgb565::BLACK,
+        foreground: Rgb565::WHITE,
+        accent: Rgb565::YELLOW,
+        selection: Rgb565::YELLOW,
+        caption: Rgb565::BLACK,
+    },
+];
@@ -0,0 +1,124 @@
+/* sin and cos for curve generation, in f32.
+
+   libm computes them in f64 even for f32, which none of the chips has hardware for. With the
+   `fast-trig` feature they come from a quarter wave table instead, interpolated in fixed point:
+   the angle becomes a 32 bit fraction of a turn, the top bits pick the quadrant and table entry
+   and the rest is the distance to the next one. Off by less than 5e-6, far below a pixel even
+   on the widest curves. */
+
+#[cfg(not(feature = "fast-trig"))]
+pub use libm::{cosf as cos, sinf as sin};
+
+#[cfg(feature = "fast-trig")]
+pub use table::{cos, sin};
+
+/* Built for tests too, so its accuracy is checked whatever the features */
+#[cfg(any(feature = "fast-trig", test))]
+mod table {
+    use core::f32::consts::TAU;
+
+    use libm::roundf;
+
+    /* Table entries per quarter turn, as a power of two */
+    const BITS: u32 = 8;
+    const STEPS: usize = 1 << BITS;
+
+    /* sin over a quarter turn in Q30 */
+    static TABLE: [i32; STEPS + 1] = quarter_wave();
+
+    pub fn sin(x: f32) -> f32 {
+        lookup(turns(x))
+    }
+
+    pub fn cos(x: f32) -> f32 {
+        lookup(turns(x).wrapping_add(1 << 30))
+    }
+
+    /* 2π in two parts, whole turns of the first one are exact as long as an f32 angle has bits for them */
+    const TAU_HIGH: f32 = 6.28125;
+    const TAU_LOW: f32 = (core::f64::consts::TAU - 6.28125) as f32;
+
+    /* Angle in 2^32ths of a turn, wrapping around just like the angle does. Whole turns are
+       taken off first: scaled as they are, far out angles would lose the digits that matter */
+    pub fn turns(x: f32) -> u32 {
+        let n = roundf(x / TAU);
+        let x = (x - n * TAU_HIGH) - n * TAU_LOW;
+        (x * (4294967296.0 / TAU)) as i32 as u32
+    }
+
+    pub fn lookup(turns: u32) -> f32 {
+        const FRACTION_BITS: u32 = 30 - BITS;
+        let quadrant = turns >> 30;
+        let within = turns & ((1 << 30) - 1);
+        /* Second and fourth quadrants read the table backwards */
+        let within = if quadrant & 1 == 1 { (1 << 30) - within } else { within };
+
+        let index = (within >> FRACTION_BITS) as usize;
+        let fraction = (within & ((1 << FRACTION_BITS) - 1)) as i64;
+        let low = TABLE[index];
+        let high = TABLE[(index + 1).min(STEPS)];
+        /* Entries are at most 2^23 apart, so only the product needs 64 bits */
+        let value = low + (((high - low) as i64 * fraction) >> FRACTION_BITS) as i32;
+
+        let value = if quadrant >= 2 { -value } else { value };
+        value as f32 / (1 << 30) as f32
+    }
+
+    /* Taylor series in Q62, evaluated by the compiler */
+    const fn quarter_wave() -> [i32; STEPS + 1] {
+        /* π/2 in Q62 */
+        const HALF_PI: i128 = 7244019458077122842;
+
+        let mut table = [0; STEPS + 1];
+        let mut k = 0;
+        while k <= STEPS {
+            let x = HALF_PI * k as i128 / STEPS as i128;
+            let x2 = (x * x) >> 62;
+            let mut term = x;
+            let mut sum = x;
+            let mut n = 1;
+            while n < 16 {
+                term = -((term * x2) >> 62) / (2 * n * (2 * n + 1));
+                sum += term;
+                n += 1;
+            }
+            table[k] = ((sum + (1 << 31)) >> 32) as i32;
+            k += 1;
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::TAU;
+
+    use super::table;
+
+    /* Every 2^12th angle of a turn, table entries and the points halfway between included */
+    #[test]
+    fn table_matches_libm() {
+        let mut worst: f64 = 0.0;
+        for step in 0..1u32 << 20 {
+            let turns = step << 12;
+            let x = turns as f64 * (TAU / 4294967296.0);
+            worst = worst
+                .max((table::lookup(turns) as f64 - libm::sin(x)).abs())
+                .max((table::lookup(turns.wrapping_add(1 << 30)) as f64 - libm::cos(x)).abs());
+        }
+        assert!(worst < 5e-6, "off by {worst}");
+    }
+
+    /* Angles given in radians, as far out as the curves take them */
+    #[test]
+    fn radians_match_libm() {
+        let mut worst: f64 = 0.0;
+        for step in -100_000..=100_000 {
+            let x = step as f32 * 0.004_567;
+            worst = worst
+                .max((table::sin(x) as f64 - libm::sin(x as f64)).abs())
+                .max((table::cos(x) as f64 - libm::cos(x as f64)).abs());
+        }
+        assert!(worst < 5e-6, "off by {worst}");
+    }
+}
@@ -0,0 +1,511 @@
+/* Turtle graphics, for artworks written as scripts.
+
+   A script is words separated by spaces, `;` comments to the end of the line:
+
+       forward n, fd n        step n pixels ahead, drawing if the pen is down
+       back n, bk n           step n pixels back
+       right a, rt a          turn a degrees clockwise
+       left a, lt a           turn a degrees anticlockwise
+       penup, pu              stop drawing
+       pendown, pd            start drawing again
+       colour r g b           draw in that colour, 0..255 each (`color` too)
+       repeat n [ ... ]       do what's in brackets n times
+       to name ... end        make `name` a word doing what's up to `end`
+
+   The turtle starts in the middle of the 320x240 design screen, facing up, drawing in the
+   theme's colour. Procedures may call themselves but take no arguments, so there's no stopping
+   them, calls and repeats only nest `MAX_NESTING` deep.
+
+   Nothing is allocated: the script is compiled into a fixed list of ops, which are run into
+   a vertex buffer the caller lends once each time the artwork is opened. Every stretch drawn
+   in one go in one colour becomes a stroke of its own. */
+
+use core::fmt;
+
+use embedded_graphics::pixelcolor::Rgb565;
+use heapless::Vec;
+
+use crate::artworks::{design, Lines, Stroke, Tip, MAX_STROKES};
+use crate::curve::Curve;
+use crate::gradient::Gradient;
+use crate::trig::{cos, sin};
+
+const MAX_OPS: usize = 256;
+const MAX_PROCEDURES: usize = 16;
+const MAX_NESTING: usize = 16;
+/* Ops run before a script is taken to be stuck */
+const MAX_STEPS: u32 = 100_000;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Problem {
+    UnknownWord,
+    NumberExpected,
+    NameExpected,
+    /* `[` without `]`, `]` without `[`, `to` without `end`, `end` without `to` */
+    Unmatched,
+    /* `to` inside brackets or another procedure */
+    NestedProcedure,
+    TooLong,
+    TooDeep,
+    TooMuchToDraw,
+}
+
+/* What went wrong, and where: a byte offset into the script */
+#[derive(Clone, Copy, Debug)]
+pub struct Error {
+    pub problem: Problem,
+    pub at: usize,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let problem = match self.problem {
+            Problem::UnknownWord => "unknown word",
+            Problem::NumberExpected => "number expected",
+            Problem::NameExpected => "name expected",
+            Problem::Unmatched => "unmatched bracket or procedure",
+            Problem::NestedProcedure => "procedure inside a block",
+            Problem::TooLong => "script too long",
+            Problem::TooDeep => "nested too deep",
+            Problem::TooMuchToDraw => "too much to draw",
+        };
+        write!(f, "{} at {}", problem, self.at)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Forward(f32),
+    Turn(f32),
+    Pen(bool),
+    Colour(Rgb565),
+    /* Pushes a count, or goes to `end` right away for none */
+    Repeat { count: u32, end: u16 },
+    /* Back to `body` until the count is used up */
+    Next { body: u16 },
+    Call(u16),
+    Return,
+    /* Over a procedure's body */
+    Skip(u16),
+}
+
+/* What closes the block being compiled */
+#[derive(Clone, Copy, PartialEq)]
+enum Closing {
+    Script,
+    Bracket,
+    End,
+}
+
+struct Compiler<'a> {
+    source: &'a str,
+    position: usize,
+    /* Each with where in the script it came from */
+    ops: Vec<(Op, u16), MAX_OPS>,
+    procedures: Vec<(&'a str, u16), MAX_PROCEDURES>,
+}
+
+impl<'a> Compiler<'a> {
+    /* Next word, or bracket, and where it starts */
+    fn word(&mut self) -> Option<(&'a str, usize)> {
+        let bytes = self.source.as_bytes();
+        loop {
+            while self.position < bytes.len() && bytes[self.position].is_ascii_whitespace() {
+                self.position += 1;
+            }
+            if bytes.get(self.position) != Some(&b';') {
+                break;
+            }
+            while self.position < bytes.len() && bytes[self.position] != b'\n' {
+                self.position += 1;
+            }
+        }
+        let start = self.position;
+        match bytes.get(start)? {
+            b'[' | b']' => self.position += 1,
+            _ => {
+                while self.position < bytes.len() && !bytes[self.position].is_ascii_whitespace() && !b"[];".contains(&bytes[self.position]) {
+                    self.position += 1;
+                }
+            }
+        }
+        Some((&self.source[start..self.position], start))
+    }
+
+    fn number(&mut self) -> Result<f32, Error> {
+        let at = self.position;
+        match self.word() {
+            Some((word, at)) => word.parse().map_err(|_| Error { problem: Problem::NumberExpected, at }),
+            None => Err(Error { problem: Problem::NumberExpected, at }),
+        }
+    }
+
+    fn emit(&mut self, op: Op, at: usize) -> Result<u16, Error> {
+        let index = self.ops.len() as u16;
+        self.ops.push((op, at as u16)).map_err(|_| Error { problem: Problem::TooLong, at })?;
+        Ok(index)
+    }
+
+    /* Words up to `closing`, whose position is `opened` for when it's missing */
+    fn block(&mut self, closing: Closing, opened: usize) -> Result<(), Error> {
+        loop {
+            let Some((word, at)) = self.word() else {
+                if closing == Closing::Script {
+                    return Ok(());
+                }
+                return Err(Error { problem: Problem::Unmatched, at: opened });
+            };
+            let op = match word {
+                "forward" | "fd" => Op::Forward(self.number()?),
+                "back" | "bk" => Op::Forward(-self.number()?),
+                "right" | "rt" => Op::Turn(self.number()?),
+                "left" | "lt" => Op::Turn(-self.number()?),
+                "penup" | "pu" => Op::Pen(false),
+                "pendown" | "pd" => Op::Pen(true),
+                "colour" | "color" => {
+                    let mut channel = || self.number().map(|value| value.clamp(0.0, 255.0) as u8);
+                    let (r, g, b) = (channel()?, channel()?, channel()?);
+                    Op::Colour(Rgb565::new(r >> 3, g >> 2, b >> 3))
+                }
+                "repeat" => {
+                    let count = self.number()?.max(0.0) as u32;
+                    match self.word() {
+                        Some(("[", _)) => {}
+                        _ => return Err(Error { problem: Problem::Unmatched, at }),
+                    }
+                    let repeat = self.emit(Op::Repeat { count, end: 0 }, at)?;
+                    self.block(Closing::Bracket, at)?;
+                    let next = self.emit(Op::Next { body: repeat + 1 }, at)?;
+                    self.ops[repeat as usize].0 = Op::Repeat { count, end: next + 1 };
+                    continue;
+                }
+                "to" if closing != Closing::Script => return Err(Error { problem: Problem::NestedProcedure, at }),
+                "to" => {
+                    let name = match self.word() {
+                        Some((name, _)) if !["[", "]", "end"].contains(&name) && name.parse::<f32>().is_err() => name,
+                        _ => return Err(Error { problem: Problem::NameExpected, at }),
+                    };
+                    let skip = self.emit(Op::Skip(0), at)?;
+                    /* Known inside its own body already */
+                    self.procedures
+                        .push((name, skip + 1))
+                        .map_err(|_| Error { problem: Problem::TooLong, at })?;
+                    self.block(Closing::End, at)?;
+                    let end = self.emit(Op::Return, at)?;
+                    self.ops[skip as usize].0 = Op::Skip(end + 1);
+                    continue;
+                }
+                "]" if closing == Closing::Bracket => return Ok(()),
+                "end" if closing == Closing::End => return Ok(()),
+                "]" | "end" => return Err(Error { problem: Problem::Unmatched, at }),
+                /* The latest definition wins */
+                name => match self.procedures.iter().rev().find(|(known, _)| *known == name) {
+                    Some(&(_, start)) => Op::Call(start),
+                    None => return Err(Error { problem: Problem::UnknownWord, at }),
+                },
+            };
+            self.emit(op, at)?;
+        }
+    }
+}
+
+/* Vertices of all trails, in design pixels, with how far along their trail they are. 12 KB */
+pub const CAPACITY: usize = 1024;
+pub type Vertices = [[f32; 3]; CAPACITY];
+const MAX_TRAILS: usize = MAX_STROKES;
+
+/* What a script drew: its trails, each with the colour it was drawn in */
+pub struct Drawn<'a> {
+    trails: Vec<(Trail<'a>, Corners<'a>, Option<Rgb565>), MAX_TRAILS>,
+}
+
+impl Drawn<'_> {
+    pub fn strokes(&self) -> Vec<Stroke<'_>, MAX_STROKES> {
+        self.trails
+            .iter()
+            .map(|(trail, corners, colour)| Stroke {
+                tip: Tip::Glyph('.'),
+                curve: trail,
+                density: 1.0,
+                spacing: 1.0,
+                lines: Lines::Outline { curve: corners, density: 1.0 },
+                color: colour.map_or(Gradient::Foreground, Gradient::Solid),
+            })
+            .collect()
+    }
+}
+
+/* Compiles and runs `script`, drawing into `vertices`. Whatever was drawn before an error is
+   kept, and comes with it */
+pub fn run<'a>(script: &str, vertices: &'a mut Vertices) -> (Drawn<'a>, Result<(), Error>) {
+    let mut compiler = Compiler {
+        source: script,
+        position: 0,
+        ops: Vec::new(),
+        procedures: Vec::new(),
+    };
+    let compiled = compiler.block(Closing::Script, 0);
+
+    let mut turtle = Turtle {
+        position: (160.0, 120.0),
+        heading: 0.0,
+        pen: true,
+        colour: None,
+        vertices,
+        length: 0,
+        trails: Vec::new(),
+        colours: Vec::new(),
+        open: false,
+    };
+    let result = compiled.and_then(|_| turtle.run(&compiler.ops));
+
+    let Turtle { vertices, trails, colours, .. } = turtle;
+    let vertices: &'a Vertices = vertices;
+    let trails = trails
+        .iter()
+        .zip(colours)
+        .map(|(&(start, end), colour)| {
+            let trail = &vertices[start as usize..end as usize];
+            (Trail(trail), Corners(trail), colour)
+        })
+        .collect();
+    (Drawn { trails }, result)
+}
+
+struct Turtle<'a> {
+    /* Design pixels */
+    position: (f32, f32),
+    /* Degrees clockwise from up */
+    heading: f32,
+    pen: bool,
+    colour: Option<Rgb565>,
+    vertices: &'a mut Vertices,
+    length: usize,
+    trails: Vec<(u16, u16), MAX_TRAILS>,
+    colours: Vec<Option<Rgb565>, MAX_TRAILS>,
+    /* Whether the last trail is still being drawn */
+    open: bool,
+}
+
+impl Turtle<'_> {
+    fn run(&mut self, ops: &[(Op, u16)]) -> Result<(), Error> {
+        let mut counts: Vec<u32, MAX_NESTING> = Vec::new();
+        let mut calls: Vec<u16, MAX_NESTING> = Vec::new();
+        let mut pc = 0;
+        let mut steps = 0;
+        while let Some(&(op, at)) = ops.get(pc) {
+            let error = |problem| Error { problem, at: at as usize };
+            steps += 1;
+            if steps > MAX_STEPS {
+                return Err(error(Problem::TooMuchToDraw));
+            }
+            pc += 1;
+            match op {
+                Op::Forward(distance) => self.forward(distance).map_err(error)?,
+                Op::Turn(degrees) => self.heading += degrees,
+                Op::Pen(down) => {
+                    self.pen = down;
+                    self.open = false;
+                }
+                Op::Colour(colour) => {
+                    self.colour = Some(colour);
+                    self.open = false;
+                }
+                Op::Repeat { count: 0, end } => pc = end as usize,
+                Op::Repeat { count, .. } => counts.push(count).map_err(|_| error(Problem::TooDeep))?,
+                Op::Next { body } => {
+                    if let Some(count) = counts.last_mut() {
+                        *count -= 1;
+                        if *count > 0 {
+                            pc = body as usize;
+                        } else {
+                            counts.pop();
+                        }
+                    }
+                }
+                Op::Call(start) => {
+                    calls.push(pc as u16).map_err(|_| error(Problem::TooDeep))?;
+                    pc = start as usize;
+                }
+                Op::Return => pc = calls.pop().map_or(ops.len(), |back| back as usize),
+                Op::Skip(to) => pc = to as usize,
+            }
+        }
+        Ok(())
+    }
+
+    fn forward(&mut self, distance: f32) -> Result<(), Problem> {
+        let angle = self.heading.to_radians();
+        let from = self.position;
+        self.position = (from.0 + distance * sin(angle), from.1 - distance * cos(angle));
+        if !self.pen {
+            return Ok(());
+        }
+        if !self.open {
+            if self.length >= CAPACITY {
+                return Err(Problem::TooMuchToDraw);
+            }
+            self.vertices[self.length] = [from.0, from.1, 0.0];
+            self.length += 1;
+            self.trails.push((self.length as u16 - 1, self.length as u16)).map_err(|_| Problem::TooMuchToDraw)?;
+            self.colours.push(self.colour).ok();
+            self.open = true;
+        }
+        if self.length >= CAPACITY {
+            return Err(Problem::TooMuchToDraw);
+        }
+        let along = self.vertices[self.length - 1][2] + distance.abs();
+        self.vertices[self.length] = [self.position.0, self.position.1, along];
+        self.length += 1;
+        if let Some(trail) = self.trails.last_mut() {
+            trail.1 = self.length as u16;
+        }
+        Ok(())
+    }
+}
+
+/* A trail drawn in one go, the parameter is the distance along it in design pixels */
+#[derive(Clone, Copy)]
+pub struct Trail<'a>(&'a [[f32; 3]]);
+
+impl Curve for Trail<'_> {
+    fn point(&self, t: f32) -> (f32, f32) {
+        let vertices = self.0;
+        let Some(last) = vertices.len().checked_sub(1) else {
+            return (0.0, 0.0);
+        };
+        let i = vertices.partition_point(|vertex| vertex[2] <= t).clamp(1, last.max(1)) - 1;
+        let (from, to) = (vertices[i], vertices[(i + 1).min(last)]);
+        let f = if to[2] > from[2] { ((t - from[2]) / (to[2] - from[2])).clamp(0.0, 1.0) } else { 0.0 };
+        design(from[0] + (to[0] - from[0]) * f, from[1] + (to[1] - from[1]) * f)
+    }
+
+    fn range(&self) -> core::ops::Range<f32> {
+        0.0..self.0.last().map_or(0.0, |vertex| vertex[2])
+    }
+}
+
+/* The same trail, the parameter counting its vertices, for lines to turn exactly at them */
+#[derive(Clone, Copy)]
+pub struct Corners<'a>(&'a [[f32; 3]]);
+
+impl Curve for Corners<'_> {
+    fn point(&self, t: f32) -> (f32, f32) {
+        let vertices = self.0;
+        match vertices.get(t as usize).or(vertices.last()) {
+            Some(vertex) => design(vertex[0], vertex[1]),
+            None => (0.0, 0.0),
+        }
+    }
+
+    fn range(&self) -> core::ops::Range<f32> {
+        0.0..self.0.len().saturating_sub(1) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use embedded_graphics::pixelcolor::Rgb565;
+
+    use super::{run, Drawn, Error, Problem, Vertices, CAPACITY};
+    use crate::artworks::{CRYSTAL_SCRIPT, FLOWER_SCRIPT, SQUARES_SCRIPT};
+
+    /* Each trail's vertices, rounded to whole design pixels, and its colour */
+    type Trails = Vec<(Vec<[i32; 3]>, Option<Rgb565>)>;
+
+    fn trails(drawn: &Drawn) -> Trails {
+        drawn
+            .trails
+            .iter()
+            .map(|(trail, _, colour)| (trail.0.iter().map(|vertex| vertex.map(|v| v.round() as i32)).collect(), *colour))
+            .collect()
+    }
+
+    fn draw(script: &str) -> (Trails, Result<(), Error>) {
+        let mut vertices: Vertices = [[0.0; 3]; CAPACITY];
+        let (drawn, result) = run(script, &mut vertices);
+        (trails(&drawn), result)
+    }
+
+    fn fault(script: &str) -> (Problem, usize) {
+        let error = draw(script).1.unwrap_err();
+        (error.problem, error.at)
+    }
+
+    #[test]
+    fn forward_turn_pen_and_colour() {
+        let (trails, result) = draw("fd 10 right 90 forward 20 lt 90 bk 5 penup fd 5 pendown colour 255 0 0 rt 90 fd 5");
+        assert!(result.is_ok());
+        /* From the middle, facing up, in the theme's colour until told otherwise */
+        assert_eq!(
+            trails,
+            [
+                (std::vec![[160, 120, 0], [160, 110, 10], [180, 110, 30], [180, 115, 35]], None),
+                (std::vec![[180, 110, 0], [185, 110, 5]], Some(Rgb565::new(31, 0, 0))),
+            ]
+        );
+        /* Each colour change starts a trail, as does putting the pen down */
+        let (trails, _) = draw("fd 1 colour 0 0 255 fd 1 color 0 0 255 fd 1 pu pd fd 1");
+        assert_eq!(trails.iter().map(|(trail, _)| trail.len()).collect::<Vec<_>>(), [2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn repeats_nest() {
+        let (trails, result) = draw("repeat 2 [ repeat 3 [ fd 1 ] rt 90 ] repeat 0 [ fd 100 ]");
+        assert!(result.is_ok());
+        assert_eq!(trails.len(), 1);
+        let (trail, _) = &trails[0];
+        assert_eq!(trail.len(), 7);
+        assert_eq!(trail[6], [163, 117, 6]);
+    }
+
+    #[test]
+    fn procedures_call_each_other_and_themselves() {
+        let (trails, result) = draw("to side fd 10 rt 90 end to square repeat 4 [ side ] end square");
+        assert!(result.is_ok());
+        assert_eq!(trails[0].0.iter().map(|v| [v[0], v[1]]).collect::<Vec<_>>(), [[160, 120], [160, 110], [170, 110], [170, 120], [160, 120]]);
+
+        /* Redefined, the latest wins from then on */
+        let (trails, _) = draw("to step fd 1 end step to step fd 2 end step");
+        assert_eq!(trails[0].0.last(), Some(&[160, 117, 3]));
+
+        /* Recursion has nothing to stop it, it's cut off at the 17th call with what it drew */
+        let script = "to spiral fd 1 spiral end spiral";
+        let (trails, result) = draw(script);
+        assert_eq!(result.map_err(|error| (error.problem, error.at)), Err((Problem::TooDeep, script.find("spiral end").unwrap())));
+        assert_eq!(trails[0].0.len(), 17);
+
+        let deep = "repeat 2 [ ".repeat(17) + &"] ".repeat(17);
+        assert_eq!(fault(&deep), (Problem::TooDeep, 16 * "repeat 2 [ ".len()));
+    }
+
+    #[test]
+    fn errors_are_placed() {
+        /* Unbalanced brackets, at the bracket or at what opened the block */
+        assert_eq!(fault("fd 1 ]"), (Problem::Unmatched, 5));
+        assert_eq!(fault("fd 1 repeat 2 [ fd 1"), (Problem::Unmatched, 5));
+        assert_eq!(fault("repeat 2 fd 1 ]"), (Problem::Unmatched, 0));
+        assert_eq!(fault("fd 1\nto x fd 1"), (Problem::Unmatched, 5));
+        assert_eq!(fault("fd 1 end"), (Problem::Unmatched, 5));
+        /* Procedures not defined, or not yet */
+        assert_eq!(fault("fd 1 wiggle"), (Problem::UnknownWord, 5));
+        assert_eq!(fault("sq to sq fd 1 end"), (Problem::UnknownWord, 0));
+        assert_eq!(fault("repeat 2 [ to sq fd 1 end ]"), (Problem::NestedProcedure, 11));
+        assert_eq!(fault("fd ; no number\n rt 1"), (Problem::NumberExpected, 16));
+        /* Nothing is drawn when the script doesn't compile */
+        assert!(draw("fd 10 wiggle").0.is_empty());
+    }
+
+    #[test]
+    fn shipped_scripts_run() {
+        for script in [FLOWER_SCRIPT, SQUARES_SCRIPT, CRYSTAL_SCRIPT] {
+            let (trails, result) = draw(script);
+            assert!(result.is_ok());
+            assert!(!trails.is_empty());
+        }
+    }
+}
@@ -0,0 +1,78 @@
+/* Maps artwork coordinates onto whatever area they are drawn into.
+
+   Artworks are laid out in a frame 1 unit tall and `aspect` units wide, y pointing down. The
+   frame is scaled as much as the area allows without changing its shape, and centred. Curves
+   may leave the frame, on screens of another shape that part shows up instead of being cut. */
+
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+#[derive(Clone, Copy)]
+pub struct Viewport {
+    origin: (f64, f64),
+    scale: f64,
+}
+
+impl Viewport {
+    pub fn fit(area: Rectangle, aspect: f64) -> Self {
+        let (width, height) = (area.size.width as f64, area.size.height as f64);
+        let scale = (width / aspect).min(height);
+        Viewport {
+            origin: (
+                area.top_left.x as f64 + (width - scale * aspect) / 2.0,
+                area.top_left.y as f64 + (height - scale) / 2.0,
+            ),
+            scale,
+        }
+    }
+
+    /* Pixels per unit */
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn position(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        ((self.origin.0 + x as f64 * self.scale) as f32, (self.origin.1 + y as f64 * self.scale) as f32)
+    }
+
+    /* Pixel containing the point. The nudge keeps points meant to be on a pixel boundary from
+       falling into the pixel before it through rounding */
+    pub fn pixel(&self, (x, y): (f32, f32)) -> Point {
+        let nudge = 1e-3;
+        Point::new(
+            (self.origin.0 + x as f64 * self.scale + nudge) as i32,
+            (self.origin.1 + y as f64 * self.scale + nudge) as i32,
+        )
+    }
+
+    pub fn pixels<T, I: Iterator<Item = (T, (f32, f32))>>(&self, points: I) -> Pixels<I> {
+        Pixels {
+            viewport: *self,
+            points,
+            last: None,
+        }
+    }
+}
+
+/* Pixels of tagged points, leaving out any which lands on the same pixel as the one before.
+   Drawing a brush there again wouldn't change anything */
+#[derive(Clone)]
+pub struct Pixels<I> {
+    viewport: Viewport,
+    points: I,
+    last: Option<Point>,
+}
+
+impl<T, I: Iterator<Item = (T, (f32, f32))>> Iterator for Pixels<I> {
+    type Item = (T, Point);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (tag, point) = self.points.next()?;
+            let pixel = self.viewport.pixel(point);
+            if self.last != Some(pixel) {
+                self.last = Some(pixel);
+                return Some((tag, pixel));
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+/* Caption panel with title, author and word-wrapped description of an artwork */
+
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyleBuilder, Rectangle},
+    text::{Baseline, Text},
+};
+
+use profont::{PROFONT_12_POINT, PROFONT_18_POINT};
+
+use crate::gallery::Artwork;
+use crate::strings::{printable, Language};
+use crate::theme::Theme;
+
+const MARGIN: u32 = 12;
+const PADDING: u32 = 8;
+
+/* Splits text into lines of at most `width` characters, breaking at spaces and '\n' */
+pub struct WordWrap<'a> {
+    rest: &'a str,
+    width: usize,
+}
+
+impl<'a> WordWrap<'a> {
+    pub fn new(text: &'a str, width: usize) -> Self {
+        WordWrap {
+            rest: text,
+            width: width.max(1),
+        }
+    }
+}
+
+impl<'a> Iterator for WordWrap<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let paragraph_end = self.rest.find('\n').unwrap_or(self.rest.len());
+        let paragraph = &self.rest[..paragraph_end];
+
+        let mut last_space = None;
+        for (count, (i, c)) in paragraph.char_indices().enumerate() {
+            if c == ' ' {
+                last_space = Some(i);
+            }
+            if count == self.width {
+                /* Break at the last space, or in the middle of a word which is too long */
+                let (end, next) = match last_space {
+                    Some(space) => (space, space + 1),
+                    None => (i, i),
+                };
+                let line = paragraph[..end].trim_end();
+                self.rest = &self.rest[next..];
+                return Some(line);
+            }
+        }
+
+        self.rest = &self.rest[(paragraph_end + 1).min(self.rest.len())..];
+        Some(paragraph)
+    }
+}
+
+fn chars_per_line(font: &MonoFont, width: u32) -> usize {
+    (width / (font.character_size.width + font.character_spacing)) as usize
+}
+
+pub fn draw_caption<D: DrawTarget<Color = Rgb565>>(display: &mut D, area: Rectangle, artwork: &Artwork, language: Language, theme: &Theme) -> Result<(), D::Error> {
+    let panel = Rectangle::new(
+        area.top_left + Size::new(MARGIN, MARGIN),
+        area.size.saturating_sub(Size::new(2 * MARGIN, 2 * MARGIN)),
+    );
+    panel
+        .into_styled(
+            PrimitiveStyleBuilder::new()
+                .stroke_color(theme.accent)
+                .stroke_width(2)
+                .fill_color(theme.caption)
+                .build(),
+        )
+        .draw(display)?;
+
+    let text_width = panel.size.width.saturating_sub(2 * PADDING);
+    let bottom = panel.top_left.y + panel.size.height as i32 - PADDING as i32;
+    let mut position = panel.top_left + Size::new(PADDING, PADDING);
+
+    let title = printable::<48>(&PROFONT_18_POINT, artwork.title.text(language));
+    position = Text::with_baseline(&title, position, MonoTextStyle::new(&PROFONT_18_POINT, theme.accent), Baseline::Top)
+        .draw(display)?;
+    position = Point::new(panel.top_left.x + PADDING as i32, position.y + PROFONT_18_POINT.character_size.height as i32 + 2);
+
+    let small = MonoTextStyle::new(&PROFONT_12_POINT, theme.foreground);
+    let line_height = PROFONT_12_POINT.character_size.height as i32;
+    let width = chars_per_line(&PROFONT_12_POINT, text_width);
+
+    let author = printable::<96>(&PROFONT_12_POINT, artwork.author.text(language));
+    for line in WordWrap::new(&author, width) {
+        Text::with_baseline(line, position, small, Baseline::Top).draw(display)?;
+        position.y += line_height;
+    }
+    position.y += line_height / 2;
+
+    let description = printable::<512>(&PROFONT_12_POINT, artwork.description.text(language));
+    for line in WordWrap::new(&description, width) {
+        if position.y + line_height > bottom {
+            break;
+        }
+        Text::with_baseline(line, position, small, Baseline::Top).draw(display)?;
+        position.y += line_height;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+/* Buttons and what they mean.
+
+   There are only three buttons, so holding `ok` doubles as a fourth one, `Back`. It leaves
+   screens where a plain `ok` already does something else. */
+
+use crate::hal::Delay;
+use crate::uptime;
+
+/* How long `ok` has to be held to count as `Back` */
+const HOLD_MS: u64 = 700;
+
+/* Debouncing algorythm */
+pub enum Event {
+    Pressed,
+    Released,
+    Nothing,
+}
+pub struct Button<T> {
+    button: T,
+    pressed: bool,
+}
+
+impl<T: ::embedded_hal::digital::v2::InputPin<Error = core::convert::Infallible>> Button<T> {
+    pub fn new(button: T) -> Self {
+        Button {
+            button,
+            pressed: true,
+        }
+    }
+    pub fn check(&mut self){
+        self.pressed = !self.button.is_low().unwrap();
+    }
+
+    pub fn poll(&mut self, delay :&mut Delay) -> Event {
+        let pressed_now = !self.button.is_low().unwrap();
+        if !self.pressed  &&  pressed_now
+        {
+            delay.delay_ms(30u32);
+            self.check();
+            if !self.button.is_low().unwrap() {
+                Event::Pressed
+            }
+            else {
+                Event::Nothing
+            }
+        }
+        else if self.pressed && !pressed_now{
+            delay.delay_ms(30u32);
+            self.check();
+            if self.button.is_low().unwrap()
+            {
+                Event::Released
+            }
+            else {
+                Event::Nothing
+            }
+        }
+        else{
+            Event::Nothing
+        }
+
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Ok,
+    /* `ok` held for `HOLD_MS` */
+    Back,
+}
+
+pub struct Controls<U, D, O> {
+    up: Button<U>,
+    down: Button<D>,
+    ok: Button<O>,
+    delay: Delay,
+    /* When `ok` went down, until it's let go or turns into `Back` */
+    ok_since: Option<u64>,
+    /* `Back` already fired, so letting go of `ok` isn't an `Ok` */
+    ok_held: bool,
+}
+
+impl<U, D, O> Controls<U, D, O>
+where
+    U: ::embedded_hal::digital::v2::InputPin<Error = core::convert::Infallible>,
+    D: ::embedded_hal::digital::v2::InputPin<Error = core::convert::Infallible>,
+    O: ::embedded_hal::digital::v2::InputPin<Error = core::convert::Infallible>,
+{
+    pub fn new(up: U, down: D, ok: O, delay: Delay) -> Self {
+        Controls {
+            up: Button::new(up),
+            down: Button::new(down),
+            ok: Button::new(ok),
+            delay,
+            ok_since: None,
+            ok_held: false,
+        }
+    }
+
+    /* Keys fire when the button is let go, except `Back` which fires while `ok` is still held */
+    pub fn poll(&mut self) -> Option<Key> {
+        /* Events are named after the pin level: the pulled-up pin goes low ("released") while
+           the button is down and back high ("pressed") when it's let go */
+        if let Event::Pressed = self.up.poll(&mut self.delay) {
+            return Some(Key::Up);
+        }
+        if let Event::Pressed = self.down.poll(&mut self.delay) {
+            return Some(Key::Down);
+        }
+        match self.ok.poll(&mut self.delay) {
+            Event::Released => self.ok_since = Some(uptime::millis()),
+            Event::Pressed => {
+                self.ok_since = None;
+                if !core::mem::take(&mut self.ok_held) {
+                    return Some(Key::Ok);
+                }
+            }
+            Event::Nothing => {}
+        }
+        match self.ok_since {
+            Some(since) if uptime::millis() - since >= HOLD_MS => {
+                self.ok_since = None;
+                self.ok_held = true;
+                Some(Key::Back)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,153 @@
+/* Display interface which sends data over SPI with DMA.
+
+   Two buffers are used in turns: while one of them is being sent the CPU fills the other, so
+   computing the next pixels overlaps with the transfer of the previous ones. Data is only
+   waited for when both buffers are busy, or before a command since D/C may only change once
+   everything in flight has left.
+
+   The bus is moved into a transfer and handed back when it's done, and the concrete types of
+   both differ between chips and HAL versions, so starting and finishing a transfer is left to
+   two closures. */
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_dma::ReadBuffer;
+use embedded_hal::digital::v2::OutputPin;
+
+/* A whole buffer of which only the first `len` bytes are sent. The transfer owns all of it, so
+   it comes back whole from `wait` */
+pub struct Filled {
+    buffer: &'static mut [u8],
+    len: usize,
+}
+
+// SAFETY: the bytes are behind a `'static` reference, so they don't move with `Filled`, and
+// nothing else can reach them while the transfer owns it. `len` never exceeds the buffer
+unsafe impl ReadBuffer for Filled {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.buffer.as_ptr(), self.len)
+    }
+}
+
+pub struct DmaInterface<BUS, TRANSFER, DC, START, WAIT> {
+    bus: Option<BUS>,
+    in_flight: Option<TRANSFER>,
+    dc: DC,
+    start: START,
+    wait: WAIT,
+    /* Buffer being filled, `filled` bytes of it are used */
+    current: Option<&'static mut [u8]>,
+    spare: Option<&'static mut [u8]>,
+    filled: usize,
+    capacity: usize,
+}
+
+impl<BUS, TRANSFER, DC, START, WAIT> DmaInterface<BUS, TRANSFER, DC, START, WAIT>
+where
+    DC: OutputPin,
+    START: FnMut(BUS, Filled) -> TRANSFER,
+    WAIT: FnMut(TRANSFER) -> (Filled, BUS),
+{
+    /* Both buffers must have the same length, which must not exceed what the DMA descriptors can cover */
+    pub fn new(bus: BUS, mut dc: DC, buffers: [&'static mut [u8]; 2], start: START, wait: WAIT) -> Self {
+        /* Data mode, commands switch it back when they're done */
+        dc.set_high().ok();
+        let [first, second] = buffers;
+        let capacity = first.len().min(second.len());
+        DmaInterface {
+            bus: Some(bus),
+            in_flight: None,
+            dc,
+            start,
+            wait,
+            current: Some(first),
+            spare: Some(second),
+            filled: 0,
+            capacity,
+        }
+    }
+
+    /* Blocks until the transfer in flight, if any, is done */
+    fn finish(&mut self) {
+        if let Some(transfer) = self.in_flight.take() {
+            let (Filled { buffer, .. }, bus) = (self.wait)(transfer);
+            self.bus = Some(bus);
+            if self.current.is_none() {
+                self.current = Some(buffer);
+            } else {
+                self.spare = Some(buffer);
+            }
+        }
+    }
+
+    /* Starts sending what has been filled so far and switches to the other buffer */
+    fn send(&mut self) {
+        if self.filled == 0 {
+            return;
+        }
+        self.finish();
+
+        let data = Filled { buffer: self.current.take().unwrap(), len: self.filled };
+        let bus = self.bus.take().unwrap();
+        self.in_flight = Some((self.start)(bus, data));
+        self.current = self.spare.take();
+        self.filled = 0;
+    }
+
+    fn push(&mut self, byte: u8) {
+        if self.filled == self.capacity {
+            self.send();
+        }
+        self.current.as_mut().unwrap()[self.filled] = byte;
+        self.filled += 1;
+    }
+
+    fn push_all(&mut self, data: DataFormat<'_>) -> Result<(), DisplayError> {
+        match data {
+            DataFormat::U8(bytes) => {
+                for chunk in bytes.chunks(self.capacity) {
+                    if self.filled + chunk.len() > self.capacity {
+                        self.send();
+                    }
+                    self.current.as_mut().unwrap()[self.filled..self.filled + chunk.len()].copy_from_slice(chunk);
+                    self.filled += chunk.len();
+                }
+            }
+            DataFormat::U8Iter(bytes) => bytes.for_each(|byte| self.push(byte)),
+            DataFormat::U16(words) => words.iter().for_each(|word| word.to_ne_bytes().into_iter().for_each(|byte| self.push(byte))),
+            DataFormat::U16BE(words) => words.iter().for_each(|word| word.to_be_bytes().into_iter().for_each(|byte| self.push(byte))),
+            DataFormat::U16LE(words) => words.iter().for_each(|word| word.to_le_bytes().into_iter().for_each(|byte| self.push(byte))),
+            DataFormat::U16BEIter(words) => words.for_each(|word| word.to_be_bytes().into_iter().for_each(|byte| self.push(byte))),
+            DataFormat::U16LEIter(words) => words.for_each(|word| word.to_le_bytes().into_iter().for_each(|byte| self.push(byte))),
+            _ => return Err(DisplayError::DataFormatNotImplemented),
+        }
+        Ok(())
+    }
+}
+
+impl<BUS, TRANSFER, DC, START, WAIT> WriteOnlyDataCommand for DmaInterface<BUS, TRANSFER, DC, START, WAIT>
+where
+    DC: OutputPin,
+    START: FnMut(BUS, Filled) -> TRANSFER,
+    WAIT: FnMut(TRANSFER) -> (Filled, BUS),
+{
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        /* Pending pixel data has to leave while D/C is still high */
+        self.send();
+        self.finish();
+
+        self.dc.set_low().map_err(|_| DisplayError::DCError)?;
+        self.push_all(cmd)?;
+        self.send();
+        self.finish();
+        self.dc.set_high().map_err(|_| DisplayError::DCError)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        self.push_all(buf)?;
+        /* Don't wait, the transfer runs while the caller computes what comes next */
+        self.send();
+        Ok(())
+    }
+}
@@ -0,0 +1,341 @@
+/* Mandelbrot and Julia sets, zoomable.
+
+   The crosshair stops on the centres of a 4x3 grid and then on the actions along the bottom:
+   up/down move it, ok zooms in 3x around it or runs the action. Points are iterated in Q4.27
+   fixed point, kept in 32 bits and widened only for a product, which takes a multiply and a
+   multiply-high on every chip, where the C3 would have to emulate f32.
+
+   The picture is computed in passes, 16 px blocks first and halving them until every pixel is
+   done, each pass skipping the pixels the one before already computed. One row of blocks is
+   done per tick, so keys are answered while it's refining. */
+
+use core::fmt::Write;
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyleBuilder},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text},
+};
+
+use heapless::String;
+
+use crate::controls::Key;
+use crate::gradient::Gradient;
+use crate::settings::Settings;
+use crate::strings::{printable, Str};
+
+/* Bits after the point, leaving 4 before it: z stays within ±16 until it escapes */
+const FRACTION: u32 = 27;
+const ONE: i64 = 1 << FRACTION;
+
+const COLUMNS: u32 = 4;
+const ROWS: u32 = 3;
+const CELLS: usize = (COLUMNS * ROWS) as usize;
+const ACTIONS: [Action; 3] = [Action::ZoomOut, Action::Set, Action::Iterations];
+
+/* Block size of the first pass, a power of two */
+const FIRST_BLOCK: u32 = 16;
+
+const ITERATIONS: [u32; 4] = [32, 64, 128, 256];
+
+/* Escape counts repeat the palette every `BANDS` */
+const BANDS: usize = 32;
+/* Starts and ends alike, so the bands blend into each other */
+static PALETTE: [Rgb565; 6] = [
+    Rgb565::new(0, 4, 12),
+    Rgb565::new(4, 26, 25),
+    Rgb565::new(29, 61, 31),
+    Rgb565::new(31, 42, 0),
+    Rgb565::new(12, 10, 0),
+    Rgb565::new(0, 4, 12),
+];
+const INSIDE: Rgb565 = Rgb565::BLACK;
+
+const CROSSHAIR_ARM: i32 = 5;
+const CROSSHAIR: Rgb565 = Rgb565::WHITE;
+
+/* Width of the whole set's view, in the units of the complex plane */
+const FULL_WIDTH: f32 = 3.6;
+const MANDELBROT_CENTRE: (f32, f32) = (-0.6, 0.0);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Action {
+    ZoomOut,
+    /* Julia set of the point under the crosshair, or back to the Mandelbrot set */
+    Set,
+    Iterations,
+}
+
+#[derive(Clone, Copy)]
+struct View {
+    centre: (i32, i32),
+    width: i64,
+}
+
+impl View {
+    const MANDELBROT: View = View {
+        centre: (fixed(MANDELBROT_CENTRE.0), fixed(MANDELBROT_CENTRE.1)),
+        width: (FULL_WIDTH * ONE as f32) as i64,
+    };
+    const JULIA: View = View {
+        centre: (0, 0),
+        width: View::MANDELBROT.width,
+    };
+}
+
+pub struct FractalExplorer {
+    /* Some(c) for the Julia set of c */
+    julia: Option<(i32, i32)>,
+    view: View,
+    /* Mandelbrot view to go back to from a Julia set */
+    mandelbrot: View,
+    iterations: usize,
+    colors: [Rgb565; BANDS],
+    /* Cell or, past the cells, action the crosshair is on */
+    stop: usize,
+    /* Last cell it was on, the one Julia sets are picked from */
+    cell: usize,
+    /* Drawn into by the last tick, keys need its size to tell where the crosshair is */
+    area: Rectangle,
+    /* Pass and row of blocks being computed, block 0 once the picture is done */
+    block: u32,
+    row: u32,
+    /* Where the crosshair was drawn, to put the picture back there */
+    crosshair: Option<Point>,
+    labels: bool,
+}
+
+impl FractalExplorer {
+    pub fn new() -> Self {
+        let mut colors = [INSIDE; BANDS];
+        for (i, color) in colors.iter_mut().enumerate() {
+            *color = Gradient::Palette(&PALETTE).at(i as f32 / BANDS as f32, INSIDE);
+        }
+        FractalExplorer {
+            julia: None,
+            view: View::MANDELBROT,
+            mandelbrot: View::MANDELBROT,
+            iterations: 1,
+            colors,
+            stop: 5,
+            cell: 5,
+            area: Rectangle::zero(),
+            block: FIRST_BLOCK,
+            row: 0,
+            crosshair: None,
+            labels: true,
+        }
+    }
+
+    pub fn key(&mut self, key: Key) {
+        let area = self.area;
+        let stops = CELLS + ACTIONS.len();
+        match key {
+            Key::Up => self.stop = (self.stop + stops - 1) % stops,
+            Key::Down => self.stop = (self.stop + 1) % stops,
+            Key::Ok if self.stop < CELLS => {
+                /* Any deeper and neighbouring pixels would be the same point */
+                if self.view.width / 3 >= 8 * area.size.width as i64 {
+                    self.view = View {
+                        centre: self.point(area, cell_centre(self.cell, area) - area.top_left),
+                        width: self.view.width / 3,
+                    };
+                    self.restart();
+                }
+            }
+            Key::Ok => match ACTIONS[self.stop - CELLS] {
+                Action::ZoomOut => {
+                    let full = if self.julia.is_some() { View::JULIA } else { View::MANDELBROT };
+                    self.view.width = (self.view.width * 3).min(full.width);
+                    if self.view.width == full.width {
+                        self.view.centre = full.centre;
+                    }
+                    self.restart();
+                }
+                Action::Set => {
+                    match self.julia {
+                        Some(_) => {
+                            self.julia = None;
+                            self.view = self.mandelbrot;
+                        }
+                        None => {
+                            self.julia = Some(self.point(area, cell_centre(self.cell, area) - area.top_left));
+                            self.mandelbrot = self.view;
+                            self.view = View::JULIA;
+                        }
+                    }
+                    self.restart();
+                }
+                Action::Iterations => {
+                    self.iterations = (self.iterations + 1) % ITERATIONS.len();
+                    self.restart();
+                }
+            },
+            Key::Back => {}
+        }
+        if self.stop < CELLS {
+            self.cell = self.stop;
+        }
+        self.labels = true;
+    }
+
+    fn restart(&mut self) {
+        self.block = FIRST_BLOCK;
+        self.row = 0;
+        /* The first pass covers it */
+        self.crosshair = None;
+    }
+
+    /* Draws the next row of blocks, and the crosshair and actions when they changed or were
+       drawn over. Returns whether anything was drawn */
+    pub fn tick<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, settings: &Settings) -> Result<bool, D::Error> {
+        let area = display.bounding_box();
+        if area != self.area {
+            self.area = area;
+            self.restart();
+        }
+        let mut drawn = false;
+        /* Rows of pixels drawn over */
+        let mut rows = 0..0;
+
+        if self.block > 0 {
+            let block = self.block;
+            let y = self.row * block;
+            for x in (0..area.size.width).step_by(block as usize) {
+                /* Computed by the previous pass */
+                if block < FIRST_BLOCK && x.is_multiple_of(2 * block) && y.is_multiple_of(2 * block) {
+                    continue;
+                }
+                let color = self.color(self.point(area, Point::new(x as i32, y as i32)));
+                display.fill_solid(&Rectangle::new(area.top_left + Point::new(x as i32, y as i32), Size::new(block, block)), color)?;
+            }
+            rows = area.top_left.y + y as i32..area.top_left.y + (y + block) as i32;
+            self.row += 1;
+            if self.row * block >= area.size.height {
+                self.block /= 2;
+                self.row = 0;
+            }
+            drawn = true;
+        }
+
+        let crosshair = (self.stop < CELLS).then(|| cell_centre(self.stop, area));
+        let overlaps = |point: Point| rows.start <= point.y + CROSSHAIR_ARM && point.y - CROSSHAIR_ARM < rows.end;
+        if let Some(old) = self.crosshair {
+            if Some(old) != crosshair {
+                self.erase_crosshair(display, area, old)?;
+                self.crosshair = None;
+                drawn = true;
+            }
+        }
+        if let Some(new) = crosshair {
+            if self.crosshair.is_none() || overlaps(new) {
+                draw_crosshair(display, new)?;
+                self.crosshair = Some(new);
+                drawn = true;
+            }
+        }
+
+        let labels_top = area.bottom_right().map_or(0, |corner| corner.y) - 11;
+        if self.labels || rows.end > labels_top {
+            self.draw_labels(display, settings, Point::new(area.top_left.x + 4, labels_top))?;
+            self.labels = false;
+            drawn = true;
+        }
+        Ok(drawn)
+    }
+
+    /* Point of the plane under pixel `pixel` of `area`, counted from its top left corner */
+    fn point(&self, area: Rectangle, pixel: Point) -> (i32, i32) {
+        let step = self.view.width / area.size.width as i64;
+        let offset = |position: i32, size: u32| (position as i64 - size as i64 / 2) * step;
+        (
+            (self.view.centre.0 as i64 + offset(pixel.x, area.size.width)) as i32,
+            (self.view.centre.1 as i64 + offset(pixel.y, area.size.height)) as i32,
+        )
+    }
+
+    fn color(&self, point: (i32, i32)) -> Rgb565 {
+        let limit = ITERATIONS[self.iterations];
+        let escaped = match self.julia {
+            Some(c) => escape(point, c, limit),
+            None => escape((0, 0), point, limit),
+        };
+        match escaped {
+            Some(n) => self.colors[n as usize % BANDS],
+            None => INSIDE,
+        }
+    }
+
+    /* Puts back the pixels under it, at full resolution even if the blocks around aren't yet */
+    fn erase_crosshair<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, area: Rectangle, centre: Point) -> Result<(), D::Error> {
+        let pixels = crosshair_points(centre).map(|point| Pixel(point, self.color(self.point(area, point - area.top_left))));
+        display.draw_iter(pixels)
+    }
+
+    /* Actions along the bottom, the one the crosshair is on marked and in the selection colour */
+    fn draw_labels<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, settings: &Settings, origin: Point) -> Result<(), D::Error> {
+        let theme = settings.theme();
+        let mut x = origin.x;
+        for (i, action) in ACTIONS.into_iter().enumerate() {
+            let selected = self.stop == CELLS + i;
+            let mut text: String<24> = String::new();
+            text.push(if selected { '>' } else { ' ' }).ok();
+            let label = match action {
+                Action::ZoomOut => Str::ZoomOut,
+                Action::Set if self.julia.is_some() => Str::Mandelbrot,
+                Action::Set => Str::Julia,
+                Action::Iterations => Str::Iterations,
+            };
+            text.push_str(&printable::<16>(&FONT_6X10, label.text(settings.language))).ok();
+            if action == Action::Iterations {
+                write!(text, " {}", ITERATIONS[self.iterations]).ok();
+            }
+            let style = MonoTextStyleBuilder::new()
+                .font(&FONT_6X10)
+                .text_color(if selected { theme.selection } else { theme.foreground })
+                .background_color(theme.background)
+                .build();
+            x = Text::with_baseline(&text, Point::new(x, origin.y), style, Baseline::Top).draw(display)?.x + 6;
+        }
+        Ok(())
+    }
+}
+
+/* Iterations before z ↦ z² + c leaves the circle of radius 2, None if it doesn't in `limit` */
+fn escape(z: (i32, i32), c: (i32, i32), limit: u32) -> Option<u32> {
+    let product = |a: i32, b: i32| a as i64 * b as i64;
+    let (mut x, mut y) = z;
+    for n in 0..limit {
+        let (x2, y2) = (product(x, x) >> FRACTION, product(y, y) >> FRACTION);
+        if x2 + y2 > 4 * ONE {
+            return Some(n);
+        }
+        /* |z| ≤ 2 here, so z² + c is back within ±16 */
+        y = (product(x, y) >> (FRACTION - 1)) as i32 + c.1;
+        x = (x2 - y2) as i32 + c.0;
+    }
+    None
+}
+
+const fn fixed(x: f32) -> i32 {
+    (x * ONE as f32) as i32
+}
+
+fn cell_centre(cell: usize, area: Rectangle) -> Point {
+    let (column, row) = (cell as u32 % COLUMNS, cell as u32 / COLUMNS);
+    area.top_left
+        + Point::new(
+            ((2 * column + 1) * area.size.width / (2 * COLUMNS)) as i32,
+            ((2 * row + 1) * area.size.height / (2 * ROWS)) as i32,
+        )
+}
+
+fn crosshair_points(centre: Point) -> impl Iterator<Item = Point> {
+    (-CROSSHAIR_ARM..=CROSSHAIR_ARM).flat_map(move |d| [centre + Point::new(d, 0), centre + Point::new(0, d)])
+}
+
+fn draw_crosshair<D: DrawTarget<Color = Rgb565>>(display: &mut D, centre: Point) -> Result<(), D::Error> {
+    display.draw_iter(crosshair_points(centre).map(|point| Pixel(point, CROSSHAIR)))
+}
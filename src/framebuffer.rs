@@ -0,0 +1,181 @@
+/* Off-screen RGB565 frame which remembers what changed since the last flush */
+
+use core::convert::Infallible;
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+};
+
+/* More regions means less overdraw but more SPI windows; rectangles are merged once this runs out */
+const MAX_DIRTY: usize = 8;
+
+/* A new region is only started when growing an existing one would add more pixels than this */
+const MERGE_SLACK: u32 = 256;
+
+pub struct Framebuffer<'a> {
+    pixels: &'a mut [Rgb565],
+    size: Size,
+    dirty: [Rectangle; MAX_DIRTY],
+    dirty_count: usize,
+}
+
+impl<'a> Framebuffer<'a> {
+    /* `pixels` may live anywhere (internal RAM, PSRAM), it must hold `size` pixels */
+    pub fn new(pixels: &'a mut [Rgb565], size: Size) -> Self {
+        assert!(pixels.len() >= (size.width * size.height) as usize, "framebuffer too small");
+        Framebuffer {
+            pixels,
+            size,
+            dirty: [Rectangle::zero(); MAX_DIRTY],
+            dirty_count: 0,
+        }
+    }
+
+    /* Sends the changed regions to `display`, each as one window and a single pixel burst */
+    pub fn flush<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D) -> Result<(), D::Error> {
+        let width = self.size.width as usize;
+        for area in &self.dirty[..self.dirty_count] {
+            let left = area.top_left.x as usize;
+            let right = left + area.size.width as usize;
+            let top = area.top_left.y as usize;
+            let bottom = top + area.size.height as usize;
+            let pixels = &self.pixels;
+            display.fill_contiguous(
+                area,
+                (top..bottom).flat_map(|y| pixels[y * width + left..y * width + right].iter().copied()),
+            )?;
+        }
+        self.dirty_count = 0;
+        Ok(())
+    }
+
+    fn mark(&mut self, area: Rectangle) {
+        let dirty = &mut self.dirty[..self.dirty_count];
+        if dirty.iter().any(|rect| contains(rect, &area)) {
+            return;
+        }
+
+        /* Grow the region which needs the fewest extra pixels to cover `area` */
+        let best = dirty
+            .iter()
+            .enumerate()
+            .map(|(i, rect)| (i, union(rect, &area)))
+            .min_by_key(|(i, grown)| area_of(grown) - area_of(&dirty[*i]));
+
+        match best {
+            Some((i, grown))
+                if area_of(&grown) - area_of(&dirty[i]) <= MERGE_SLACK + area_of(&area)
+                    || self.dirty_count == MAX_DIRTY =>
+            {
+                dirty[i] = grown;
+                self.coalesce(i);
+            }
+            _ => {
+                self.dirty[self.dirty_count] = area;
+                self.dirty_count += 1;
+            }
+        }
+    }
+
+    /* A grown region may now overlap others, fold those into it */
+    fn coalesce(&mut self, mut index: usize) {
+        let mut i = 0;
+        while i < self.dirty_count {
+            if i != index && overlaps(&self.dirty[i], &self.dirty[index]) {
+                self.dirty[index] = union(&self.dirty[index], &self.dirty[i]);
+                self.dirty_count -= 1;
+                self.dirty.swap(i, self.dirty_count);
+                if index == self.dirty_count {
+                    index = i;
+                }
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+fn area_of(rect: &Rectangle) -> u32 {
+    rect.size.width * rect.size.height
+}
+
+fn contains(outer: &Rectangle, inner: &Rectangle) -> bool {
+    match inner.bottom_right() {
+        Some(bottom_right) => outer.contains(inner.top_left) && outer.contains(bottom_right),
+        None => true,
+    }
+}
+
+fn overlaps(a: &Rectangle, b: &Rectangle) -> bool {
+    !a.intersection(b).is_zero_sized()
+}
+
+fn union(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    let (Some(a_end), Some(b_end)) = (a.bottom_right(), b.bottom_right()) else {
+        return if a.is_zero_sized() { *b } else { *a };
+    };
+    Rectangle::with_corners(
+        Point::new(a.top_left.x.min(b.top_left.x), a.top_left.y.min(b.top_left.y)),
+        Point::new(a_end.x.max(b_end.x), a_end.y.max(b_end.y)),
+    )
+}
+
+impl OriginDimensions for Framebuffer<'_> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for Framebuffer<'_> {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounding_box();
+        let width = self.size.width as usize;
+        /* Most pixels land next to the previous one, only look for a region when leaving it */
+        let mut current = Rectangle::zero();
+        for Pixel(point, color) in pixels {
+            if !bounds.contains(point) {
+                continue;
+            }
+            self.pixels[point.y as usize * width + point.x as usize] = color;
+            if !current.contains(point) {
+                self.mark(Rectangle::new(point, Size::new(1, 1)));
+                current = *self.dirty[..self.dirty_count]
+                    .iter()
+                    .find(|rect| rect.contains(point))
+                    .unwrap_or(&Rectangle::zero());
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        let width = self.size.width as usize;
+        let left = area.top_left.x as usize;
+        for y in area.rows() {
+            let start = y as usize * width + left;
+            self.pixels[start..start + area.size.width as usize].fill(color);
+        }
+        self.mark(area);
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.pixels.fill(color);
+        self.dirty[0] = self.bounding_box();
+        self.dirty_count = 1;
+        Ok(())
+    }
+}
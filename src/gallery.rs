@@ -0,0 +1,244 @@
+/* Gallery entries: what is shown in the menu and in the caption panel, and what is drawn when
+   one is opened */
+
+use core::fmt;
+
+use heapless::Vec;
+
+use crate::artworks::{self, Grown, Stroke, MAX_STROKES};
+use crate::lsystem::{Overflow, Path, Paths};
+use crate::scene_file::{self, Scene};
+use crate::strings::Str;
+use crate::turtle::{self, Drawn};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+    /* Loaded from a scene file, see `scene_file` */
+    Scene(&'static str),
+    Hypotrochoid,
+    Lace,
+    Epitrochoid,
+    FractalPlant,
+    KochSnowflake,
+    Dragon,
+    /* Drawn by a turtle script, see `turtle` */
+    Turtle(&'static str),
+    /* Played with rather than drawn, see `interactive` */
+    Roses,
+    Lissajous,
+    Spirograph,
+    Fractals,
+    Life,
+    Meadow,
+    Wireframes,
+}
+
+pub struct Artwork {
+    pub title: Str,
+    pub author: Str,
+    /* Math behind the picture, '\n' starts a new paragraph */
+    pub description: Str,
+    pub kind: Kind,
+}
+
+/* Room for what opening an artwork makes, lent to one artwork at a time. 28 KB */
+pub struct Workspace {
+    vertices: turtle::Vertices,
+    paths: Paths<{ artworks::PATH_VERTICES }>,
+}
+
+impl Workspace {
+    pub const fn new() -> Self {
+        Workspace {
+            vertices: [[0.0; 3]; turtle::CAPACITY],
+            paths: Paths::new(),
+        }
+    }
+}
+
+/* What an opened artwork draws. Only one is alive at a time and there's no heap to box a scene in */
+#[allow(clippy::large_enum_variant)]
+pub enum Opened<'a> {
+    Strokes(&'static [Stroke<'static>]),
+    Scene(Scene<'a>),
+    /* A grown path and the stroke drawing it */
+    Grown(Path<'a>, &'static Stroke<'static>),
+    Turtle(Drawn<'a>),
+}
+
+impl Opened<'_> {
+    pub fn strokes(&self) -> Vec<Stroke<'_>, MAX_STROKES> {
+        match self {
+            Opened::Strokes(strokes) => strokes.iter().copied().collect(),
+            Opened::Scene(scene) => scene.strokes(),
+            Opened::Grown(path, stroke) => [Stroke { curve: path, ..**stroke }].into_iter().collect(),
+            Opened::Turtle(drawn) => drawn.strokes(),
+        }
+    }
+}
+
+/* Why an artwork couldn't be drawn, or not all of it */
+pub enum Error {
+    Script(turtle::Error),
+    Scene(scene_file::Error),
+    /* Grown without what didn't fit */
+    Growth(Overflow),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Script(error) => write!(f, "script: {}", error),
+            Error::Scene(error) => write!(f, "scene: {}", error),
+            Error::Growth(overflow) => write!(f, "growth: {}", overflow),
+        }
+    }
+}
+
+impl Artwork {
+    /* Scripts are run, scenes loaded and L-systems grown from `seed` again every time they're
+       opened. A script keeps what it drew before an error and an L-system what fitted, a scene
+       with one draws nothing */
+    pub fn open<'a>(&self, seed: u32, workspace: &'a mut Workspace) -> (Opened<'a>, Option<Error>) {
+        match self.kind {
+            Kind::Scene(scene) => match scene_file::load(scene, seed, &mut workspace.paths) {
+                Ok(scene) => (Opened::Scene(scene), None),
+                Err(error) => (Opened::Strokes(&[]), Some(Error::Scene(error))),
+            },
+            Kind::Hypotrochoid => (Opened::Strokes(&artworks::HYPOTROCHOID_STROKES), None),
+            Kind::Lace => (Opened::Strokes(&artworks::LACE_STROKES), None),
+            Kind::Epitrochoid => (Opened::Strokes(&artworks::EPITROCHOID_STROKES), None),
+            Kind::FractalPlant => grow(seed, &artworks::PLANT, &mut workspace.paths),
+            Kind::KochSnowflake => grow(seed, &artworks::KOCH_SNOWFLAKE, &mut workspace.paths),
+            Kind::Dragon => grow(seed, &artworks::DRAGON, &mut workspace.paths),
+            Kind::Turtle(script) => {
+                let (drawn, result) = turtle::run(script, &mut workspace.vertices);
+                (Opened::Turtle(drawn), result.err().map(Error::Script))
+            }
+            /* Draws itself */
+            Kind::Roses | Kind::Lissajous | Kind::Spirograph | Kind::Fractals | Kind::Life | Kind::Meadow | Kind::Wireframes => (Opened::Strokes(&[]), None),
+        }
+    }
+}
+
+fn grow<'a, const N: usize>(seed: u32, grown: &'static Grown, paths: &'a mut Paths<N>) -> (Opened<'a>, Option<Error>) {
+    let overflow = paths.grow(core::slice::from_ref(&grown.growth), seed).err();
+    (Opened::Grown(paths.path(0), &grown.stroke), overflow.map(|(_, overflow)| Error::Growth(overflow)))
+}
+
+pub static GALLERY: [Artwork; 19] = [
+    Artwork {
+        title: Str::Eye,
+        author: Str::AdaptedBy,
+        description: Str::EyeDescription,
+        kind: Kind::Scene(artworks::EYE_SCENE),
+    },
+    Artwork {
+        title: Str::LollipopGuy,
+        author: Str::AdaptedBy,
+        description: Str::LollipopGuyDescription,
+        kind: Kind::Scene(artworks::LOLLIPOP_GUY_SCENE),
+    },
+    Artwork {
+        title: Str::Garden,
+        author: Str::AdaptedBy,
+        description: Str::GardenDescription,
+        kind: Kind::Scene(artworks::GARDEN_SCENE),
+    },
+    Artwork {
+        title: Str::Hypotrochoid,
+        author: Str::Original,
+        description: Str::HypotrochoidDescription,
+        kind: Kind::Hypotrochoid,
+    },
+    Artwork {
+        title: Str::Lace,
+        author: Str::Original,
+        description: Str::LaceDescription,
+        kind: Kind::Lace,
+    },
+    Artwork {
+        title: Str::Epitrochoid,
+        author: Str::Original,
+        description: Str::EpitrochoidDescription,
+        kind: Kind::Epitrochoid,
+    },
+    Artwork {
+        title: Str::FractalPlant,
+        author: Str::Original,
+        description: Str::FractalPlantDescription,
+        kind: Kind::FractalPlant,
+    },
+    Artwork {
+        title: Str::KochSnowflake,
+        author: Str::Original,
+        description: Str::KochSnowflakeDescription,
+        kind: Kind::KochSnowflake,
+    },
+    Artwork {
+        title: Str::Dragon,
+        author: Str::Original,
+        description: Str::DragonDescription,
+        kind: Kind::Dragon,
+    },
+    Artwork {
+        title: Str::TurtleFlower,
+        author: Str::Original,
+        description: Str::TurtleFlowerDescription,
+        kind: Kind::Turtle(artworks::FLOWER_SCRIPT),
+    },
+    Artwork {
+        title: Str::TurningSquares,
+        author: Str::Original,
+        description: Str::TurningSquaresDescription,
+        kind: Kind::Turtle(artworks::SQUARES_SCRIPT),
+    },
+    Artwork {
+        title: Str::IceCrystal,
+        author: Str::Original,
+        description: Str::IceCrystalDescription,
+        kind: Kind::Turtle(artworks::CRYSTAL_SCRIPT),
+    },
+    Artwork {
+        title: Str::Roses,
+        author: Str::Original,
+        description: Str::RosesDescription,
+        kind: Kind::Roses,
+    },
+    Artwork {
+        title: Str::Lissajous,
+        author: Str::Original,
+        description: Str::LissajousDescription,
+        kind: Kind::Lissajous,
+    },
+    Artwork {
+        title: Str::Spirograph,
+        author: Str::Original,
+        description: Str::SpirographDescription,
+        kind: Kind::Spirograph,
+    },
+    Artwork {
+        title: Str::Fractals,
+        author: Str::Original,
+        description: Str::FractalsDescription,
+        kind: Kind::Fractals,
+    },
+    Artwork {
+        title: Str::Life,
+        author: Str::Original,
+        description: Str::LifeDescription,
+        kind: Kind::Life,
+    },
+    Artwork {
+        title: Str::Meadow,
+        author: Str::Original,
+        description: Str::MeadowDescription,
+        kind: Kind::Meadow,
+    },
+    Artwork {
+        title: Str::Wireframes,
+        author: Str::Original,
+        description: Str::WireframesDescription,
+        kind: Kind::Wireframes,
+    },
+];
@@ -0,0 +1,78 @@
+/* Gallery entries which are played with rather than looked at.
+
+   They get every key but `Back`, which leaves them, and are ticked all the time in between so
+   they can animate. `tick` draws whatever changed since the last one and says whether anything
+   did, the way `Animation::tick` does. */
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+
+use crate::controls::Key;
+use crate::fractal::FractalExplorer;
+use crate::gallery::Kind;
+use crate::life::Life;
+use crate::lissajous::Oscilloscope;
+use crate::meadow::Meadow;
+use crate::rose_explorer::{Favourite, RoseExplorer};
+use crate::settings::Settings;
+use crate::spirograph::SpirographExplorer;
+use crate::wireframe::Wireframe;
+
+/* Only the one being played with is alive, so the biggest explorer sets the size */
+#[allow(clippy::large_enum_variant)]
+pub enum Interactive {
+    Roses(RoseExplorer),
+    Lissajous(Oscilloscope),
+    Spirograph(SpirographExplorer),
+    Fractals(FractalExplorer),
+    Life(Life),
+    Meadow(Meadow),
+    Wireframes(Wireframe),
+}
+
+impl Interactive {
+    /* None for artworks which are simply drawn. `seed` is a fresh random number */
+    pub fn new(kind: Kind, now: u64, seed: u32) -> Option<Self> {
+        match kind {
+            Kind::Roses => Some(Interactive::Roses(RoseExplorer::new(Favourite::DEFAULT))),
+            Kind::Lissajous => Some(Interactive::Lissajous(Oscilloscope::new(now))),
+            Kind::Spirograph => Some(Interactive::Spirograph(SpirographExplorer::new())),
+            Kind::Fractals => Some(Interactive::Fractals(FractalExplorer::new())),
+            Kind::Life => Some(Interactive::Life(Life::new(seed, now))),
+            Kind::Meadow => Some(Interactive::Meadow(Meadow::new(seed))),
+            Kind::Wireframes => Some(Interactive::Wireframes(Wireframe::new(now))),
+            _ => None,
+        }
+    }
+
+    pub fn key(&mut self, key: Key, now: u64) {
+        match self {
+            Interactive::Roses(explorer) => explorer.key(key),
+            Interactive::Lissajous(oscilloscope) => oscilloscope.key(key),
+            Interactive::Spirograph(explorer) => explorer.key(key),
+            Interactive::Fractals(explorer) => explorer.key(key),
+            Interactive::Life(life) => life.key(key, now),
+            Interactive::Meadow(meadow) => meadow.key(key),
+            Interactive::Wireframes(wireframe) => wireframe.key(key),
+        }
+    }
+
+    pub fn tick<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, settings: &Settings, now: u64) -> Result<bool, D::Error> {
+        match self {
+            Interactive::Roses(explorer) => explorer.tick(display, settings),
+            Interactive::Lissajous(oscilloscope) => oscilloscope.tick(display, settings, now),
+            Interactive::Spirograph(explorer) => explorer.tick(display, settings),
+            Interactive::Fractals(explorer) => explorer.tick(display, settings),
+            Interactive::Life(life) => life.tick(display, settings, now),
+            Interactive::Meadow(meadow) => meadow.tick(display, settings),
+            Interactive::Wireframes(wireframe) => wireframe.tick(display, settings, now),
+        }
+    }
+
+    /* Rose to add to the main menu, once */
+    pub fn take_favourite(&mut self) -> Option<Favourite> {
+        match self {
+            Interactive::Roses(explorer) => explorer.take_favourite(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+/* Conway's Game of Life on a torus, see `cells` for the rules.
+
+   Cells are 4 px squares, as many as fit the area, up to 128 across, and only cells which were
+   born or died are drawn. Random fills come from the seed the game was opened with.
+
+   ok pauses and resumes. While running up/down make it faster/slower, while paused up steps
+   one generation and down fills the grid anew: random, a glider gun, a pulsar, random again. */
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+use crate::cells::{Cells, GLIDER_GUN, PULSAR};
+use crate::controls::Key;
+use crate::random::Random;
+use crate::settings::Settings;
+
+const CELL: u32 = 4;
+
+/* Milliseconds between generations */
+const DELAYS: [u64; 6] = [1000, 500, 200, 100, 50, 20];
+
+/* Share of cells alive in a random fill */
+const DENSITY: f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Fill {
+    Random,
+    GliderGun,
+    Pulsar,
+}
+
+pub struct Life {
+    cells: Cells,
+    /* Top left corner of the grid, centred in the area */
+    origin: Point,
+    fill: Fill,
+    random: Random,
+    generation: u32,
+    speed: usize,
+    paused: bool,
+    /* One generation to do while paused */
+    step: bool,
+    /* Whole grid to draw, after a fill */
+    redraw: bool,
+    next_generation: u64,
+}
+
+impl Life {
+    /* Random fills come from `seed`, `now` only times the generations */
+    pub fn new(seed: u32, now: u64) -> Self {
+        Life {
+            cells: Cells::new(0, 0),
+            origin: Point::zero(),
+            fill: Fill::Random,
+            random: Random::new(seed),
+            generation: 0,
+            speed: 3,
+            paused: false,
+            step: false,
+            redraw: true,
+            next_generation: now,
+        }
+    }
+
+    pub fn key(&mut self, key: Key, now: u64) {
+        match key {
+            Key::Ok => {
+                self.paused = !self.paused;
+                self.next_generation = now;
+            }
+            Key::Up if self.paused => self.step = true,
+            Key::Down if self.paused => {
+                self.fill = match self.fill {
+                    Fill::Random => Fill::GliderGun,
+                    Fill::GliderGun => Fill::Pulsar,
+                    Fill::Pulsar => Fill::Random,
+                };
+                self.redraw = true;
+            }
+            Key::Up => self.speed = (self.speed + 1).min(DELAYS.len() - 1),
+            Key::Down => self.speed = self.speed.saturating_sub(1),
+            Key::Back => {}
+        }
+    }
+
+    /* Draws a generation when one is due, returns whether anything was drawn */
+    pub fn tick<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, settings: &Settings, now: u64) -> Result<bool, D::Error> {
+        let theme = settings.theme();
+        if core::mem::take(&mut self.redraw) {
+            let area = display.bounding_box();
+            self.cells = Cells::new(area.size.width / CELL, (area.size.height / CELL) as usize);
+            self.origin = area.top_left
+                + Point::new(
+                    ((area.size.width - self.cells.columns() * CELL) / 2) as i32,
+                    ((area.size.height - self.cells.rows() as u32 * CELL) / 2) as i32,
+                );
+            self.generation = 0;
+            match self.fill {
+                Fill::Random => self.cells.scatter(&mut self.random, DENSITY),
+                Fill::GliderGun => self.cells.place(&GLIDER_GUN),
+                Fill::Pulsar => self.cells.place(&PULSAR),
+            }
+
+            display.clear(theme.background)?;
+            for y in 0..self.cells.rows() {
+                draw_cells(display, self.origin, self.cells.columns(), y, self.cells.row(y), theme.foreground)?;
+            }
+            return Ok(true);
+        }
+
+        let due = !self.paused && now >= self.next_generation;
+        if self.cells.rows() == 0 || !due && !core::mem::take(&mut self.step) {
+            return Ok(false);
+        }
+        self.next_generation = (self.next_generation + DELAYS[self.speed]).max(now);
+
+        let (origin, columns) = (self.origin, self.cells.columns());
+        self.cells.step(|y, was, is| {
+            draw_cells(display, origin, columns, y, is & !was, theme.foreground)?;
+            draw_cells(display, origin, columns, y, was & !is, theme.background)
+        })?;
+        self.generation += 1;
+        Ok(true)
+    }
+}
+
+/* Cells of row `y` set in `mask`, 1 px apart */
+fn draw_cells<D: DrawTarget<Color = Rgb565>>(display: &mut D, origin: Point, columns: u32, y: usize, mask: u128, color: Rgb565) -> Result<(), D::Error> {
+    for x in (0..columns).filter(|&x| (mask >> x) & 1 == 1) {
+        let corner = origin + Point::new((x * CELL) as i32, (y as u32 * CELL) as i32);
+        display.fill_solid(&Rectangle::new(corner, Size::new(CELL - 1, CELL - 1)), color)?;
+    }
+    Ok(())
+}
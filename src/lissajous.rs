@@ -0,0 +1,194 @@
+/* Lissajous figures, drawn the way an oscilloscope in XY mode shows them.
+
+   The phase drifts a little every frame, which makes the figure look like it turns. Each frame
+   the last one is traced again in the background colour to erase it, rather than clearing the
+   screen, so only the pixels of the two traces are sent. Tracing it again costs more sines than
+   keeping its points would, but no 5 KB buffer. */
+
+use core::f32::consts::PI;
+use core::fmt::Write;
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyleBuilder},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text},
+};
+
+use heapless::String;
+
+use crate::animation::FRAME_MS;
+use crate::controls::Key;
+use crate::curve::{Curve, Lissajous};
+use crate::polyline::{self, Pen};
+use crate::settings::Settings;
+use crate::strings::{printable, Str};
+use crate::viewport::Viewport;
+
+/* a:b, from the figures every physics book shows to ones which take a while to close */
+const RATIOS: [(u32, u32); 8] = [(1, 1), (1, 2), (1, 3), (2, 3), (3, 4), (3, 5), (4, 5), (5, 6)];
+
+/* Per radian of t and unit of the higher frequency, enough for smooth lines at full size */
+const DENSITY: f32 = 16.0;
+
+const MAX_DRIFT: i32 = 5;
+
+/* Left of the figure, for the values */
+const COLUMN_WIDTH: u32 = 92;
+
+/* Frame 1 unit square, a bit of margin for the line width */
+const CENTRE: (f32, f32) = (0.5, 0.5);
+const AMPLITUDE: f32 = 0.46;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Ratio,
+    Phase,
+    Drift,
+    Amplitude,
+}
+
+pub struct Oscilloscope {
+    ratio: usize,
+    /* Half degrees, the smallest drift step */
+    phase: i32,
+    /* Half degrees of phase per frame, 0 holds the figure still */
+    drift: i32,
+    /* Percent of the frame */
+    amplitude: u32,
+    field: Field,
+    /* Figure on screen, to be erased by the next frame */
+    trace: Option<(Lissajous, Viewport)>,
+    next_frame: u64,
+    redraw: bool,
+}
+
+impl Oscilloscope {
+    pub fn new(now: u64) -> Self {
+        Oscilloscope {
+            ratio: 3,
+            phase: 0,
+            drift: 1,
+            amplitude: 100,
+            field: Field::Ratio,
+            trace: None,
+            next_frame: now,
+            redraw: true,
+        }
+    }
+
+    pub fn key(&mut self, key: Key) {
+        let up = key == Key::Up;
+        match (key, self.field) {
+            (Key::Ok, field) => {
+                self.field = match field {
+                    Field::Ratio => Field::Phase,
+                    Field::Phase => Field::Drift,
+                    Field::Drift => Field::Amplitude,
+                    Field::Amplitude => Field::Ratio,
+                }
+            }
+            (Key::Up | Key::Down, Field::Ratio) => {
+                self.ratio = if up { (self.ratio + 1) % RATIOS.len() } else { (self.ratio + RATIOS.len() - 1) % RATIOS.len() };
+            }
+            (Key::Up | Key::Down, Field::Phase) => self.phase += if up { 30 } else { -30 },
+            (Key::Up | Key::Down, Field::Drift) => {
+                self.drift = (self.drift + if up { 1 } else { -1 }).clamp(-MAX_DRIFT, MAX_DRIFT);
+            }
+            (Key::Up | Key::Down, Field::Amplitude) => {
+                self.amplitude = if up { (self.amplitude + 10).min(100) } else { (self.amplitude - 10).max(10) };
+            }
+            (Key::Back, _) => return,
+        }
+        self.redraw = true;
+    }
+
+    /* Draws a frame when one is due or something changed, returns whether it did */
+    pub fn tick<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, settings: &Settings, now: u64) -> Result<bool, D::Error> {
+        let due = self.drift != 0 && now >= self.next_frame;
+        if !due && !self.redraw {
+            return Ok(false);
+        }
+        if due {
+            self.phase += self.drift;
+            self.next_frame = (self.next_frame + FRAME_MS).max(now);
+        }
+        self.phase = self.phase.rem_euclid(720);
+
+        let theme = settings.theme();
+        let pen = Pen {
+            width: settings.line_width.max(1) as f32,
+            color: theme.background,
+            background: theme.background,
+        };
+        if let Some((figure, viewport)) = self.trace {
+            trace(display, &figure, viewport, pen)?;
+        }
+
+        let area = display.bounding_box();
+        let figure_area = Rectangle::new(
+            area.top_left + Size::new(COLUMN_WIDTH, 0),
+            Size::new(area.size.width.saturating_sub(COLUMN_WIDTH), area.size.height),
+        );
+        let viewport = Viewport::fit(figure_area, 1.0);
+        let (a, b) = RATIOS[self.ratio];
+        let amplitude = AMPLITUDE * self.amplitude as f32 / 100.0;
+        let figure = Lissajous {
+            centre: CENTRE,
+            amplitude: (amplitude, amplitude),
+            frequency: (a, b),
+            phase: self.phase as f32 * PI / 360.0,
+        };
+        let pen = Pen {
+            color: theme.foreground,
+            ..pen
+        };
+        trace(display, &figure, viewport, pen)?;
+        self.trace = Some((figure, viewport));
+
+        self.draw_values(display, settings)?;
+        self.redraw = false;
+        Ok(true)
+    }
+
+    /* Parameters down the left edge, the one being changed marked and in the selection colour */
+    fn draw_values<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, settings: &Settings) -> Result<(), D::Error> {
+        let theme = settings.theme();
+        let origin = display.bounding_box().top_left + Point::new(4, 4);
+        let fields = [Field::Ratio, Field::Phase, Field::Drift, Field::Amplitude];
+        for (i, field) in fields.into_iter().enumerate() {
+            let label = match field {
+                Field::Ratio => Str::Ratio,
+                Field::Phase => Str::Phase,
+                Field::Drift => Str::Drift,
+                Field::Amplitude => Str::Amplitude,
+            };
+            let mut text: String<24> = String::new();
+            text.push(if field == self.field { '>' } else { ' ' }).ok();
+            text.push_str(&printable::<16>(&FONT_6X10, label.text(settings.language))).ok();
+            match field {
+                Field::Ratio => write!(text, " {}:{}", RATIOS[self.ratio].0, RATIOS[self.ratio].1),
+                Field::Phase => write!(text, " {}", self.phase / 2),
+                Field::Drift => write!(text, " {}", self.drift),
+                Field::Amplitude => write!(text, " {}%", self.amplitude),
+            }
+            .ok();
+            /* Padded and on a background, so a shorter value covers up a longer one */
+            while text.len() < 15 && text.push(' ').is_ok() {}
+            let style = MonoTextStyleBuilder::new()
+                .font(&FONT_6X10)
+                .text_color(if field == self.field { theme.selection } else { theme.foreground })
+                .background_color(theme.background)
+                .build();
+            Text::with_baseline(&text, origin + Point::new(0, 12 * i as i32), style, Baseline::Top).draw(display)?;
+        }
+        Ok(())
+    }
+}
+
+fn trace<D: DrawTarget<Color = Rgb565>>(display: &mut D, figure: &Lissajous, viewport: Viewport, pen: Pen) -> Result<(), D::Error> {
+    let (a, b) = figure.frequency;
+    let points = (figure as &dyn Curve).sample(DENSITY * a.max(b) as f32).with_end();
+    polyline::draw_polyline(display, points.map(|(_, point)| (viewport.position(point), pen.color)), pen)
+}
@@ -11,13 +11,12 @@ use esp32s3_hal as hal;
 use esp32c3_hal as hal;
 
 use hal::{
-    adc::{AdcConfig, Attenuation, ADC, ADC2},
     clock::ClockControl,
     peripherals::Peripherals,
-    gpio::*,
     prelude::*,
     spi,
     timer::TimerGroup,
+    Rng,
     Rtc,
     IO,
     Delay,
@@ -25,99 +24,111 @@ use hal::{
 
 use mipidsi::Orientation;
 
+use art::{animation, artworks, cells, curve, gradient, lsystem, mesh, polyline, random, scene_file, strings, theme, turtle, viewport};
+#[cfg(feature = "strip")]
+use art::strip;
+
+mod caption;
+mod controls;
+#[cfg(feature = "dma")]
+mod dma_interface;
+mod fractal;
+#[cfg(feature = "framebuffer")]
+mod framebuffer;
+mod gallery;
+mod interactive;
+mod life;
+mod lissajous;
+mod meadow;
+mod menu;
+mod rose_explorer;
+mod screen;
+mod settings;
+mod spirograph;
+mod status_bar;
+mod uptime;
+mod wireframe;
+
+use animation::Animation;
+use controls::{Controls, Key};
+#[cfg(feature = "dma")]
+use dma_interface::DmaInterface;
+use artworks::Drawing;
+use gallery::Workspace;
+use interactive::Interactive;
+use menu::{Entry, Menu};
+use rose_explorer::{Favourite, RoseExplorer};
+#[cfg(not(feature = "strip"))]
+use art::scene::Scene;
+use screen::Screen;
+use settings::Settings;
+use status_bar::StatusBar;
+use strings::Str;
+
+#[cfg(not(feature = "dma"))]
 use display_interface_spi::SPIInterfaceNoCS;
 
-use core::f32::consts::PI;
-use libm::{sin, cos};
-
 use embedded_graphics::{
-    prelude::RgbColor,
-    mono_font::{
-        ascii::FONT_10X20,
-        MonoTextStyleBuilder,
-        MonoTextStyle,
-    },
     prelude::*,
-    text::{Alignment, Text},
-    Drawable,
-    pixelcolor::*,
-    primitives::{Circle, PrimitiveStyleBuilder, PrimitiveStyle, Rectangle},
-    text::*,
-    image::Image,
-    geometry::*,
     draw_target::DrawTarget,
 };
 
-use embedded_hal;
+#[cfg(any(feature = "framebuffer", feature = "strip"))]
+use embedded_graphics::pixelcolor::Rgb565;
+
+use core::ptr::addr_of_mut;
 
-use profont::{PROFONT_24_POINT, PROFONT_18_POINT};
+#[cfg(feature = "dma")]
+use hal::{
+    dma::{DmaPriority, DmaTransfer},
+    spi::dma::WithDmaSpi2,
+};
+#[cfg(all(feature = "dma", any(feature = "esp32s3", feature = "esp32c3")))]
+use hal::gdma::Gdma;
+#[cfg(all(feature = "dma", any(feature = "esp32", feature = "esp32s2")))]
+use hal::pdma::Dma;
 
 use esp_println::println;
 use esp_backtrace as _;
 
-/* Debouncing algorythm */
-pub enum Event {
-    Pressed,
-    Released,
-    Nothing,
-}
-pub struct Button<T> {
-    button: T,
-    pressed: bool,
-}
+/* Whether the status bar is shown after boot, can be changed in settings */
+const SHOW_STATUS_BAR: bool = true;
 
-impl<T: ::embedded_hal::digital::v2::InputPin<Error = core::convert::Infallible>> Button<T> {
-    pub fn new(button: T) -> Self {
-        Button {
-            button,
-            pressed: true,
-        }
-    }
-    pub fn check(&mut self){
-        self.pressed = !self.button.is_low().unwrap();
-    }
+/* Sent in turns by DMA, one is filled while the other one is on the wire. 4 KB fit in two descriptors */
+#[cfg(feature = "dma")]
+static mut DMA_BUFFERS: [[u8; 4096]; 2] = [[0; 4096]; 2];
 
-    pub fn poll(&mut self, delay :&mut Delay) -> Event {
-        let pressed_now = !self.button.is_low().unwrap();
-        if !self.pressed  &&  pressed_now
-        {
-            delay.delay_ms(30 as u32);
-            self.check();
-            if !self.button.is_low().unwrap() {
-                Event::Pressed
-            }
-            else {
-                Event::Nothing
-            }
-        }
-        else if self.pressed && !pressed_now{
-            delay.delay_ms(30 as u32);
-            self.check();
-            if self.button.is_low().unwrap()
-            {
-                Event::Released
-            }
-            else {
-                Event::Nothing
-            }
-        }
-        else{
-            Event::Nothing
-        }
-        
-    }
-}
+#[cfg(all(feature = "strip", feature = "framebuffer"))]
+compile_error!("`strip` is meant for chips without room for a framebuffer, enable only one of them");
+
+/* One 320x16 band, 10 KB */
+#[cfg(feature = "strip")]
+static mut STRIP_BUFFER: [Rgb565; 320 * 16] = [Rgb565::WHITE; 320 * 16];
+
+/* Whole 320x240 frame, 150 KB. Could as well be placed in PSRAM on chips which have it */
+#[cfg(feature = "framebuffer")]
+static mut FRAMEBUFFER: [Rgb565; 320 * 240] = [Rgb565::WHITE; 320 * 240];
+
+/* Lent to whichever artwork is opened */
+static mut WORKSPACE: Workspace = Workspace::new();
+
+/* Roses saved in the explorer, kept until reset */
+const MAX_FAVOURITES: usize = 8;
 
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take();
 
-    #[cfg(any(feature = "esp32"))]
+    #[cfg(feature = "esp32")]
     let mut system = peripherals.DPORT.split();
     #[cfg(any(feature = "esp32s2", feature = "esp32s3", feature = "esp32c3"))]
     let mut system = peripherals.SYSTEM.split();
 
-    let mut clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+    uptime::init(&clocks);
+
+    /* Seeds of everything grown at random */
+    let mut rng = Rng::new(peripherals.RNG);
 
     // Disable the RTC and TIMG watchdog timers
     let mut rtc = Rtc::new(peripherals.RTC_CNTL);
@@ -125,6 +136,8 @@ fn main() -> ! {
     let mut wdt0 = timer_group0.wdt;
     let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
     let mut wdt1 = timer_group1.wdt;
+    #[cfg(feature = "esp32")]
+    uptime::start(timer_group1.timer0, &clocks);
     
     rtc.rwdt.disable();
     wdt0.disable();
@@ -143,7 +156,7 @@ fn main() -> ! {
     let backlight = io.pins.gpio4;
 
     /* Then set backlight (set_low() - display lights up when signal is in 0, set_high() - opposite case(for example.)) */
-    let mut backlight = backlight.into_push_pull_output();
+    let _backlight = backlight.into_push_pull_output();
     //backlight.set_low().unwrap();
 
     /* Configure SPI */
@@ -156,511 +169,305 @@ fn main() -> ! {
         80u32.MHz(),
         spi::SpiMode::Mode0,
         &mut system.peripheral_clock_control,
-        &mut clocks,
+        &clocks,
     );
 
+    #[cfg(not(feature = "dma"))]
     let di = SPIInterfaceNoCS::new(spi, dc.into_push_pull_output());
+
+    /* Pixels are streamed by DMA while the CPU computes the next ones */
+    #[cfg(feature = "dma")]
+    let mut tx_descriptors = [0u32; 8 * 3];
+    #[cfg(feature = "dma")]
+    let mut rx_descriptors = [0u32; 8 * 3];
+    #[cfg(feature = "dma")]
+    let di = {
+        #[cfg(any(feature = "esp32s3", feature = "esp32c3"))]
+        let dma_channel = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control).channel0;
+        #[cfg(any(feature = "esp32", feature = "esp32s2"))]
+        let dma_channel = Dma::new(system.dma, &mut system.peripheral_clock_control).spi2channel;
+
+        let spi = spi.with_dma(dma_channel.configure(
+            false,
+            &mut tx_descriptors,
+            &mut rx_descriptors,
+            DmaPriority::Priority0,
+        ));
+        let [first, second] = unsafe { &mut *addr_of_mut!(DMA_BUFFERS) };
+        DmaInterface::new(
+            spi,
+            dc.into_push_pull_output(),
+            [first, second],
+            |spi, buffer| spi.dma_write(buffer).unwrap(),
+            |transfer| transfer.wait(),
+        )
+    };
     let reset = rst.into_push_pull_output();
     let mut delay = Delay::new(&clocks);
 
 
-    let mut display = mipidsi::Builder::ili9341_rgb565(di)
-        .with_display_size(240 as u16, 320 as u16)
-        .with_framebuffer_size(240 as u16, 320 as u16)
+    let display = mipidsi::Builder::ili9341_rgb565(di)
+        .with_display_size(240u16, 320u16)
+        .with_framebuffer_size(240u16, 320u16)
         .with_orientation(Orientation::LandscapeInverted(true))
         .init(&mut delay, Some(reset))
         .unwrap();
         
     println!("Initialized");
 
-    display.clear(Rgb565::WHITE);
-
-    let eye_plate_tab = display.bounding_box().center() - Size::new(80, 30);
-    let lollipop_plate_tab = display.bounding_box().center() - Size::new(80,0);
-    let garden_plate_tab = display.bounding_box().center() + Size::new(0, 30) - Size::new(80, 0);
-
-    let pointer_offset = Size::new(15, 10);
-
-
-
-     
-    let mut button_up = Button::new(io.pins.gpio0.into_pull_up_input());
-    let mut button_down  = Button::new(io.pins.gpio1.into_pull_up_input());
-    let mut button_ok = Button::new(io.pins.gpio8.into_pull_up_input());
-
- 
-    Text::new("Eye",
-            eye_plate_tab,
-            MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),
-    )
-    .draw(&mut display)
-    .unwrap();
-
-    Text::new("Lollipop Guy",
-            lollipop_plate_tab, //- Size::new(0, 15), 
-            MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),           
-    )
-    .draw(&mut display)
-    .unwrap();
-
-    Text::new("Garden",
-            garden_plate_tab, 
-            MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),
-    )
-    .draw(&mut display)
-    .unwrap();
-
-    Circle::new(eye_plate_tab - pointer_offset, 10)
-        .into_styled(
-                    PrimitiveStyleBuilder::new()
-                        .stroke_color(Rgb565::BLACK)
-                        .stroke_width(1)
-                        .fill_color(Rgb565::BLACK)
-                        .build(),
-        )
-        .draw(&mut display)
-        .unwrap();
+    #[cfg(feature = "framebuffer")]
+    let mut display = Screen::new(display, unsafe { &mut *addr_of_mut!(FRAMEBUFFER) });
+    #[cfg(not(feature = "framebuffer"))]
+    let mut display = Screen::new(display);
 
-     
+    let mut settings = Settings::new(SHOW_STATUS_BAR);
+    display.clear(settings.theme().background).unwrap();
 
+    let menu_origin = display.bounding_box().center() - Size::new(80, 30);
 
-    let mut pointer_position : u8 = 1;
-    let mut last_pointer_position : u8 = 1;
+    let mut controls = Controls::new(
+        io.pins.gpio0.into_pull_up_input(),
+        io.pins.gpio1.into_pull_up_input(),
+        io.pins.gpio8.into_pull_up_input(),
+        delay,
+    );
 
-    loop {
+    let mut favourites: heapless::Vec<Favourite, MAX_FAVOURITES> = heapless::Vec::new();
+    let mut menu = Menu::new(menu_origin);
+    menu.draw(&mut display, &settings, &favourites).unwrap();
 
-        if last_pointer_position != pointer_position
-        { 
-            Rectangle::new( match last_pointer_position {
-                                        1 => eye_plate_tab,
-                                        2 => lollipop_plate_tab,
-                                        3 => garden_plate_tab,
-                                        _ => Point::new(0,0), 
-                                    } - Size::new(17,12), Size::new(15, 15))
-            .into_styled(
-                PrimitiveStyleBuilder::new()
-                    .fill_color(Rgb565::WHITE)
-                    .build(),
-            )
-            .draw(&mut display)
-            .unwrap();
-
-            Circle::new(match pointer_position {
-                                        1 => eye_plate_tab,
-                                        2 => lollipop_plate_tab,
-                                        3 => garden_plate_tab,
-                                        _ => Point::new(0,0), 
-                                } - pointer_offset, 10)
-            .into_styled(
-                        PrimitiveStyleBuilder::new()
-                            .stroke_color(Rgb565::BLACK)
-                            .stroke_width(1)
-                            .fill_color(Rgb565::BLACK)
-                            .build(),
-            )
-            .draw(&mut display)
-            .unwrap();
-             
-
-            last_pointer_position = pointer_position
-        }
+    let mut status_bar = StatusBar::new(settings.status_bar);
+    status_bar.draw(&mut display, settings.theme()).unwrap();
+    display.present().unwrap();
 
-        if let Event::Pressed = button_up.poll(&mut delay)
+    /* Samples per frame when animation is on, kept between artworks */
+    let mut animation_rate = animation::DEFAULT_RATE;
+
+    let workspace = unsafe { &mut *addr_of_mut!(WORKSPACE) };
+
+    loop {
+
+        let key = controls.poll();
+        let last = menu.selected();
+        let scrolled = match key {
+            Some(Key::Up) => {
+                println!("pressed up");
+                menu.up(&favourites)
+            }
+            Some(Key::Down) => {
+                println!("pressed down");
+                menu.down(&favourites)
+            }
+            _ => false,
+        };
+        if scrolled
         {
-            println!("pressed up");
-            if pointer_position == 1 { pointer_position = 3; }
-            else{ pointer_position -= 1; }
+            display.clear(settings.theme().background).unwrap();
+            status_bar.draw(&mut display, settings.theme()).unwrap();
+            menu.draw(&mut display, &settings, &favourites).unwrap();
+            display.present().unwrap();
         }
-        if let Event::Pressed = button_down.poll(&mut delay)
+        else if menu.selected() != last
         {
-            println!("pressed down");
-            if pointer_position == 3{ pointer_position = 1; }
-            else{ pointer_position += 1;}
+            menu.move_pointer(&mut display, &settings, last).unwrap();
+            display.present().unwrap();
         }
 
-
-        if let Event::Pressed = button_ok.poll(&mut delay)
+        if let Some(Key::Ok) = key
         {
-            display.clear(Rgb565::WHITE);
-             
-            if pointer_position == 1
+            let entry = menu.entry(&favourites);
+            if let Entry::Settings = entry
             {
-                let default_style = MonoTextStyleBuilder::new()
-                    .font(&FONT_10X20)
-                    .text_color(RgbColor::BLACK)
-                    .build();
-
-                let mut vt;
-                let mut x;
-                let mut y;
-                for i in 0..13200 {
-                    vt = i as f64 / (20.0 * PI as f64);
-                    if i < 8000 {
-                        x = (vt - 50.0) * sin(vt);
-                    } else {
-                        x = (vt + 20.0) * sin(vt);
+                let mut selected = 0;
+                let mut redraw = true;
+                loop
+                {
+                    if redraw
+                    {
+                        display.clear(settings.theme().background).unwrap();
+                        status_bar.draw(&mut display, settings.theme()).unwrap();
+                        settings.draw(&mut display, menu_origin, selected).unwrap();
+                        display.present().unwrap();
+                        redraw = false;
+                    }
+
+                    let key = controls.poll();
+                    if let Some(Key::Back) = key {break;}
+                    if let Some(Key::Up) = key
+                    {
+                        selected = (selected + settings::ITEMS.len() - 1) % settings::ITEMS.len();
+                        redraw = true;
                     }
-                    y = (vt - 50.0) * cos(vt);
-                    if i < 8000 {
-                        Text::with_alignment("'", Point::new((x + 160.0) as i32, (y + 125.0) as i32), default_style,  Alignment::Center)
-                            .draw(&mut display)
-                            .unwrap();
-                    } else {
-                        Text::with_alignment("|", Point::new((x + 160.0) as i32, (y + 125.0) as i32), default_style,  Alignment::Center)
-                            .draw(&mut display)
-                            .unwrap();
+                    if let Some(Key::Down) = key
+                    {
+                        selected = (selected + 1) % settings::ITEMS.len();
+                        redraw = true;
+                    }
+                    if let Some(Key::Ok) = key
+                    {
+                        if !settings.activate(settings::ITEMS[selected]) {break;}
+                        status_bar.enabled = settings.status_bar;
+                        redraw = true;
                     }
                 }
-                 
+                display.clear(settings.theme().background).unwrap();
+                status_bar.draw(&mut display, settings.theme()).unwrap();
+                menu.draw(&mut display, &settings, &favourites).unwrap();
+                display.present().unwrap();
+                continue;
+            }
+
+            /* Explorers draw themselves as keys come, until `Back` */
+            let explorer = match entry {
+                Entry::Artwork(artwork) => Interactive::new(artwork.kind, uptime::millis(), rng.random()),
+                Entry::Favourite(rose) => Some(Interactive::Roses(RoseExplorer::new(rose))),
+                Entry::Settings => None,
+            };
+            if let Some(mut explorer) = explorer
+            {
+                let title = match entry { Entry::Artwork(artwork) => artwork.title, _ => Str::Roses };
+                status_bar.set_artwork(title.text(settings.language));
+                let canvas = status_bar.canvas(display.bounding_box());
+                display.clear(settings.theme().background).unwrap();
+                status_bar.draw(&mut display, settings.theme()).unwrap();
                 loop
                 {
-                    if let Event::Pressed = button_ok.poll(&mut delay) {break;}
+                    if explorer.tick(&mut display.clipped(&canvas), &settings, uptime::millis()).unwrap() {
+                        display.present().unwrap();
+                    }
+                    match controls.poll() {
+                        Some(Key::Back) => break,
+                        Some(key) => explorer.key(key, uptime::millis()),
+                        None => {}
+                    }
+                    if let Some(rose) = explorer.take_favourite() {
+                        if favourites.push(rose).is_err() {
+                            println!("No room for more favourites");
+                        }
+                    }
                 }
-                display.clear(Rgb565::WHITE);
-                 
-
-                Text::new("Eye",
-                        eye_plate_tab,
-                        MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),
-                )
-                .draw(&mut display)
-                .unwrap();
-
-                Text::new("Lollipop Guy",
-                        lollipop_plate_tab, //- Size::new(0, 15), 
-                        MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),           
-                )
-                .draw(&mut display)
-                .unwrap();
-
-                Text::new("Garden",
-                        garden_plate_tab, 
-                        MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),
-                )
-                .draw(&mut display)
-                .unwrap();
-
-                Circle::new(eye_plate_tab - pointer_offset, 10)
-                    .into_styled(
-                                PrimitiveStyleBuilder::new()
-                                    .stroke_color(Rgb565::BLACK)
-                                    .stroke_width(1)
-                                    .fill_color(Rgb565::BLACK)
-                                    .build(),
-                    )
-                    .draw(&mut display)
-                    .unwrap();
-                 
-
-
-                pointer_position = 1;
-                last_pointer_position = 1;
+                display.clear(settings.theme().background).unwrap();
+                status_bar.draw(&mut display, settings.theme()).unwrap();
+                menu.draw(&mut display, &settings, &favourites).unwrap();
+                display.present().unwrap();
+                continue;
             }
-            else if pointer_position == 2
+
+            let Entry::Artwork(artwork) = entry else { continue };
+            status_bar.set_artwork(artwork.title.text(settings.language));
+
+            /* L-system artworks grow anew every time they're opened, scripts are run and
+               scenes loaded again */
+            let seed = rng.random();
+            println!("Grown from seed {}", seed);
+            let (opened, error) = artwork.open(seed, &mut *workspace);
+            if let Some(error) = error {
+                println!("{} {}", artwork.title.text(settings.language), error);
+            }
+
+            /* Artworks only get the area below the status bar, so they can't overwrite it */
+            let canvas = status_bar.canvas(display.bounding_box());
+            let strokes = opened.strokes();
+            /* `line_width` 0 draws with the original glyph brushes */
+            let drawing = Drawing {
+                strokes: &strokes,
+                line_width: settings.line_width,
+                theme: settings.theme(),
+            };
+            let mut show_caption = false;
+            let mut redraw = true;
+            let mut playback: Option<Animation> = None;
+            let mut started = 0;
+
+            loop
             {
-                let default_style = MonoTextStyleBuilder::new()
-                    .font(&FONT_10X20)
-                    .text_color(RgbColor::BLACK)
-                    .build();
-
-                let mut vt;
-                let mut x;
-                let mut y;
-
-                //body
-                for i in 0..7000 {
-                    vt = i as f64 / (40.0 * PI as f64);
-                    x = (vt - 50.0) * sin(vt);
-                    y = (vt + 50.0) *  cos(vt);
-                    if i < 6500 || i > 6900 {
-                        Text::with_alignment("'", Point::new((x + 220.0) as i32, (y + 200.0) as i32), default_style,  Alignment::Center)
-                            .draw(&mut display)
-                            .unwrap();
+                let mut finished = false;
+                if redraw
+                {
+                    /* Bands overwrite the whole canvas, no need to blank it first */
+                    if !cfg!(feature = "strip") || settings.animation {
+                        display.clear(settings.theme().background).unwrap();
                     }
-                    
-                }
-                 
-
-                //head
-                for i in 0..7000 {
-                    vt = i as f64 / (60.0 * PI as f64);
-                    x = (vt + 50.0) * cos(vt);
-                    y = (vt -  50.0) * sin(vt);
-                    
-                    Text::with_alignment("'", Point::new((x + 220.0) as i32, (y + 60.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
+                    status_bar.draw(&mut display, settings.theme()).unwrap();
 
+                    started = uptime::millis();
+                    if settings.animation {
+                        playback = Some(Animation::new(drawing, animation_rate, started));
+                    }
+                    else {
+                        #[cfg(not(feature = "strip"))]
+                        drawing.draw(&mut display.clipped(&canvas)).unwrap();
+                        #[cfg(feature = "strip")]
+                        strip::render_banded(&drawing, &mut display, canvas, unsafe { &mut *addr_of_mut!(STRIP_BUFFER) }, settings.theme().background).unwrap();
+                        finished = true;
+                    }
+                    redraw = false;
                 }
-                 
-
-                //eyes
-                for i in 0..1300 {
-                    vt = i as f64 / (20.0 * PI as f64);
-                    x = (vt - 15.0) * sin(vt);
-                    y = (vt -  15.0) * cos(vt);
-                    
-                    Text::with_alignment("'", Point::new((x + 200.0) as i32, (y + 60.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-
-                for i in 0..1300 {
-                    vt = i as f64 / (20.0 * PI as f64);
-                    x = (vt - 15.0) * sin(vt);
-                    y = (vt -  15.0) * cos(vt);
-                    
-                    Text::with_alignment("'", Point::new((x + 240.0) as i32, (y + 60.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
 
-                //hand
-
-                let mut b;
-                for a in (125..175).rev() {
-                    b = a;
-                    Text::with_alignment("-", Point::new(a, b), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-
-                //lollipop
-
-                for i in 0..3300 {
-                    vt = i as f64 / (30.0 * PI as f64);
-                    x = (vt - 30.0) * sin(vt);
-                    y = (vt -  30.0) * cos(vt);
-                    
-                    Text::with_alignment("'", Point::new((x + 110.0) as i32, (y + 110.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
+                if let Some(animation) = &mut playback
+                {
+                    if animation.tick(&mut display.clipped(&canvas), uptime::millis()).unwrap() {
+                        display.present().unwrap();
+                    }
+                    if animation.is_done() {
+                        /* Next artwork starts at the speed this one ended with */
+                        animation_rate = animation.rate();
+                        playback = None;
+                        finished = true;
+                    }
                 }
-                 
 
-                loop
+                if finished
                 {
-                    if let Event::Pressed = button_ok.poll(&mut delay) {break;}
-                }
-                display.clear(Rgb565::WHITE);
-                 
-                Text::new("Eye",
-                        eye_plate_tab,
-                        MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),
-                )
-                .draw(&mut display)
-                .unwrap();
-
-                Text::new("Lollipop Guy",
-                        lollipop_plate_tab, //- Size::new(0, 15), 
-                        MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),           
-                )
-                .draw(&mut display)
-                .unwrap();
-
-                Text::new("Garden",
-                        garden_plate_tab, 
-                        MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),
-                )
-                .draw(&mut display)
-                .unwrap();
-
-                Circle::new(eye_plate_tab - pointer_offset, 10)
-                    .into_styled(
-                                PrimitiveStyleBuilder::new()
-                                    .stroke_color(Rgb565::BLACK)
-                                    .stroke_width(1)
-                                    .fill_color(Rgb565::BLACK)
-                                    .build(),
-                    )
-                    .draw(&mut display)
-                    .unwrap();
-                 
-
-                pointer_position = 1;
-                last_pointer_position = 1;
-            }
-            else if pointer_position == 3
-            {
-                let default_style = MonoTextStyleBuilder::new()
-                    .font(&FONT_10X20)
-                    .text_color(RgbColor::BLACK)
-                    .build();
-
-                let mut n = 6.0;
-                let mut d = 71.0;    
-                let mut a;
-                let mut r;
-                let mut x;
-                let mut y;
-
-                for t in 0..361 {
-                    a = t as f64 * d * (PI as f64 / 60.0);
-                    r = 30.0 * sin(n * a);
-                    x = r * cos(a);
-                    y = r * sin(a);
-
-                    Text::with_alignment("o", Point::new((x + 35.0) as i32, (y + 180.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-                let pos_x = 1;
-                for pos_y in 0..60 {
-                    Text::with_alignment("|", Point::new(pos_x + 34, pos_y + 180), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-
-                n = 7.0;
-                d = 19.0;
-                for t in 0..700 {
-                    a = t as f64 * d * (PI as f64 / 300.0);
-                    r = 30.0 * sin(n * a);
-                    x = r * cos(a);
-                    y = r * sin(a);
-
-                    Text::with_alignment("o", Point::new((x + 90.0) as i32, (y + 140.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
+                    display.present().unwrap();
+                    let render_ms = uptime::millis() - started;
+                    println!("Rendered {} in {} ms", artwork.title.text(settings.language), render_ms);
+                    status_bar.set_render_time(render_ms);
+                    status_bar.draw(&mut display, settings.theme()).unwrap();
+                    display.present().unwrap();
                 }
-                 
 
-                for pos_y in 0..100 {
-                    Text::with_alignment("|", Point::new(pos_x + 89, pos_y + 140), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-
-                n = 2.0;
-                d = 39.0;
-                for t in 0..500 {
-                    a = t as f64 * d * (PI as f64 / 150.0);
-                    r = 30.0 * sin(n * a);
-                    x = r * cos(a);
-                    y = r * sin(a);
-
-                    Text::with_alignment("S", Point::new((x + 140.0) as i32, (y + 190.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
+                let key = controls.poll();
+                if let Some(Key::Back) = key {break;}
 
-                for pos_y in 0..50 {
-                    Text::with_alignment("|", Point::new(pos_x + 139, pos_y + 190), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-
-                n = 8.0;
-                d = 27.0;
-                for t in 0..1000 {
-                    a = t as f64 * d * (PI as f64 / 230.0);
-                    r = 30.0 * sin(n * a);
-                    x = r * cos(a);
-                    y = r * sin(a);
-
-                    Text::with_alignment("o", Point::new((x + 243.0) as i32, (y + 200.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-                for pos_y in 0..85 {
-                    Text::with_alignment("|", Point::new(pos_x + 242, pos_y + 200), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-
-                n = 5.0;
-                d = 97.0;
-                for t in 0..700 {
-                    a = t as f64 * d * (PI as f64 / 150.0);
-                    r = 30.0 * sin(n * a);
-                    x = r * cos(a);
-                    y = r * sin(a);
-
-                    Text::with_alignment("o", Point::new((x + 290.0) as i32, (y + 155.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-                for pos_y in 0..85 {
-                    Text::with_alignment("|", Point::new(pos_x + 289, pos_y + 155), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
-
-                n = 6.0;
-                d = 71.0;
-                for t in 0..2500 {
-                    a = t as f64 * d * (PI as f64 / 1200.0);
-                    r = 80.0 * sin(n * a);
-                    x = r * cos(a);
-                    y = r * sin(a);
-
-                    Text::with_alignment("o", Point::new((x + 200.0) as i32, (y + 90.0) as i32), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
+                /* While drawing: ok pauses, up/down change the speed, up steps when paused */
+                if let Some(animation) = &mut playback
+                {
+                    match key {
+                        Some(Key::Ok) => animation.toggle_pause(uptime::millis()),
+                        Some(Key::Up) if animation.is_paused() => {
+                            animation.step(&mut display.clipped(&canvas)).unwrap();
+                            display.present().unwrap();
+                        }
+                        Some(Key::Up) => animation.faster(),
+                        Some(Key::Down) => animation.slower(),
+                        _ => {}
+                    }
+                    if key.is_some() {
+                        println!("{} samples per frame{}", animation.rate(), if animation.is_paused() { ", paused" } else { "" });
+                    }
+                    continue;
                 }
-                 
 
-                for pos_y in 0..100 {
-                    Text::with_alignment("|", Point::new(pos_x + 199, pos_y + 140), default_style,  Alignment::Center)
-                        .draw(&mut display)
-                        .unwrap();
-                }
-                 
+                if let Some(Key::Ok) = key {break;}
 
-                loop
+                /* up/down on the finished render toggles the caption panel */
+                if let Some(Key::Up | Key::Down) = key
                 {
-                    if let Event::Pressed = button_ok.poll(&mut delay) {break;}
+                    show_caption = !show_caption;
+                    if show_caption {
+                        caption::draw_caption(&mut display.clipped(&canvas), canvas, artwork, settings.language, settings.theme()).unwrap();
+                        display.present().unwrap();
+                    }
+                    else {
+                        redraw = true;
+                    }
                 }
-                display.clear(Rgb565::WHITE);
-                 
-                Text::new("Eye",
-                        eye_plate_tab,
-                        MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),
-                )
-                .draw(&mut display)
-                .unwrap();
-
-                Text::new("Lollipop Guy",
-                        lollipop_plate_tab, //- Size::new(0, 15), 
-                        MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),           
-                )
-                .draw(&mut display)
-                .unwrap();
-
-                Text::new("Garden",
-                        garden_plate_tab, 
-                        MonoTextStyle::new(&PROFONT_18_POINT, Rgb565::BLACK),
-                )
-                .draw(&mut display)
-                .unwrap();
-
-                Circle::new(eye_plate_tab - pointer_offset, 10)
-                    .into_styled(
-                                PrimitiveStyleBuilder::new()
-                                    .stroke_color(Rgb565::BLACK)
-                                    .stroke_width(1)
-                                    .fill_color(Rgb565::BLACK)
-                                    .build(),
-                    )
-                    .draw(&mut display)
-                    .unwrap();
-                 
-
-                pointer_position = 1;
-                last_pointer_position = 1;
             }
+            display.clear(settings.theme().background).unwrap();
+            status_bar.draw(&mut display, settings.theme()).unwrap();
+            menu.draw(&mut display, &settings, &favourites).unwrap();
+            display.present().unwrap();
         }
     }
-}
\ No newline at end of file
+}
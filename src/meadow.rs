@@ -0,0 +1,238 @@
+/* A garden grown from a seed: how many flowers, where, how tall, with how many petals, how
+   their Maurer roses are strung and in what colours all come from `Random` seeded by it. The
+   first seed comes from the chip's random number generator, the same seed always grows the
+   same garden, on any chip.
+
+   The seed is shown in the top left corner. up grows another garden, down goes back to the one
+   before. ok starts entering a seed, one hex digit at a time: up/down change the digit, ok
+   moves on to the next one, and after the last one the garden is grown. */
+
+use core::f32::consts::PI;
+use core::fmt::Write;
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_9X15, MonoTextStyleBuilder},
+    pixelcolor::Rgb565,
+    prelude::*,
+    text::{Baseline, Text},
+};
+
+use heapless::{String, Vec};
+
+use crate::artworks::{self, design, ASPECT, STEM};
+use crate::controls::Key;
+use crate::curve::{Curve, Maurer, Rose};
+use crate::gradient::Gradient;
+use crate::lsystem::{Growth, Paths};
+use crate::polyline::{self, Pen};
+use crate::random::Random;
+use crate::settings::Settings;
+use crate::strings::{printable, Str};
+use crate::viewport::Viewport;
+
+const MAX_FLOWERS: usize = 7;
+/* Enough for 7 stems even if none of their leaves is pruned */
+const STEM_VERTICES: usize = MAX_FLOWERS * artworks::STEM_VERTICES;
+const DIGITS: u32 = 8;
+
+/* Design pixels: flowers are spread over the width, stems grow from the bottom edge, and heads
+   stay clear of the seed */
+const WIDTH: f32 = 320.0;
+const GROUND: f32 = 240.0;
+const TOP: f32 = 24.0;
+
+/* Corners every half a degree */
+const OUTLINE_DENSITY: f32 = 360.0 / PI;
+
+struct Flower {
+    rose: Rose,
+    degrees: f32,
+    hue: u16,
+    stem: f32,
+}
+
+/* Maurer roses step by `degrees` 360 times. Steps sharing a big factor with 360 come back to
+   the start after only a few points, those are left out */
+fn flower(random: &mut Random, x: f32, radius: f32) -> Flower {
+    let y = random.between(TOP + radius, GROUND - 40.0);
+    let degrees = loop {
+        let degrees = 1 + random.below(359);
+        if gcd(degrees, 360) <= 10 {
+            break degrees;
+        }
+    };
+    Flower {
+        rose: Rose { centre: design(x, y), radius: radius / 240.0, petals: 2 + random.below(6) },
+        degrees: degrees as f32,
+        /* Anything but the stems' greens */
+        hue: ((200 + random.below(200)) % 360) as u16,
+        stem: GROUND - y,
+    }
+}
+
+/* 3 to 7 flowers, each in a slot of its own across the width, nudged about in it */
+fn plant(seed: u32) -> Vec<Flower, MAX_FLOWERS> {
+    let mut random = Random::new(seed);
+    let count = 3 + random.below(MAX_FLOWERS as u32 - 2);
+    let slot = (WIDTH - 20.0) / count as f32;
+    let mut flowers = Vec::new();
+    for i in 0..count {
+        let radius = random.between(16.0, (slot * 0.6).min(36.0));
+        let x = 10.0 + slot * (i as f32 + 0.5) + random.between(-0.2, 0.2) * slot;
+        flowers.push(flower(&mut random, x.clamp(radius, WIDTH - radius), radius)).ok();
+    }
+    flowers
+}
+
+const fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+pub struct Meadow {
+    seed: u32,
+    previous: Option<u32>,
+    /* Seeds of the gardens grown with up */
+    random: Random,
+    /* Hex digit being entered, from the left */
+    digit: Option<u32>,
+    flowers: Vec<Flower, MAX_FLOWERS>,
+    stems: Paths<STEM_VERTICES>,
+    /* Flowers drawn so far, one per tick */
+    drawn: usize,
+    redraw: bool,
+    /* Only the seed changed */
+    values: bool,
+}
+
+impl Meadow {
+    pub fn new(seed: u32) -> Self {
+        Meadow {
+            seed,
+            previous: None,
+            random: Random::new(seed),
+            digit: None,
+            flowers: Vec::new(),
+            stems: Paths::new(),
+            drawn: 0,
+            redraw: true,
+            values: false,
+        }
+    }
+
+    pub fn key(&mut self, key: Key) {
+        match (key, self.digit) {
+            (Key::Up, None) => {
+                self.previous = Some(self.seed);
+                self.seed = self.random.next_u32();
+            }
+            (Key::Down, None) => match self.previous {
+                Some(previous) => self.previous = Some(core::mem::replace(&mut self.seed, previous)),
+                None => return,
+            },
+            (Key::Ok, None) => {
+                self.digit = Some(0);
+                self.values = true;
+                return;
+            }
+            (Key::Up | Key::Down, Some(digit)) => {
+                let shift = 4 * (DIGITS - 1 - digit);
+                let value = (self.seed >> shift) & 0xf;
+                let value = if key == Key::Up { (value + 1) & 0xf } else { value.wrapping_sub(1) & 0xf };
+                self.seed = self.seed & !(0xf << shift) | value << shift;
+                self.values = true;
+                return;
+            }
+            (Key::Ok, Some(digit)) if digit + 1 < DIGITS => {
+                self.digit = Some(digit + 1);
+                self.values = true;
+                return;
+            }
+            (Key::Ok, Some(_)) => self.digit = None,
+            (Key::Back, _) => return,
+        }
+        self.redraw = true;
+    }
+
+    /* Grows the garden anew after a new seed, otherwise draws its next flower. Returns whether
+       it drew anything */
+    pub fn tick<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, settings: &Settings) -> Result<bool, D::Error> {
+        let theme = settings.theme();
+        if core::mem::take(&mut self.redraw) {
+            self.flowers = plant(self.seed);
+            let stems: Vec<Growth, MAX_FLOWERS> = self
+                .flowers
+                .iter()
+                .map(|flower| {
+                    let (x, y) = flower.rose.centre;
+                    artworks::stem(x * 240.0, y * 240.0, flower.stem)
+                })
+                .collect();
+            /* Can't overflow, there's room for the longest stems */
+            self.stems.grow(&stems, self.seed).ok();
+            self.drawn = 0;
+            display.clear(theme.background)?;
+            self.draw_seed(display, settings)?;
+            return Ok(true);
+        }
+        let Some(flower) = self.flowers.get(self.drawn) else {
+            if core::mem::take(&mut self.values) {
+                self.draw_seed(display, settings)?;
+                return Ok(true);
+            }
+            return Ok(false);
+        };
+
+        let viewport = Viewport::fit(display.bounding_box(), ASPECT);
+        let pen = Pen {
+            width: settings.line_width.max(1) as f32,
+            color: theme.foreground,
+            background: theme.background,
+        };
+        let stem: &dyn Curve = &self.stems.path(self.drawn);
+        let corners = stem.sample(1.0).with_end().map(|(_, point)| (viewport.position(point), STEM));
+        polyline::draw_polyline(display, corners, pen)?;
+
+        /* Outline in the rose's colour, the rose itself shading on around the wheel */
+        let color = Gradient::Hue { from: flower.hue, to: flower.hue + 40 };
+        let outline = (&flower.rose as &dyn Curve).sample(OUTLINE_DENSITY).with_end();
+        let start = color.at(0.0, theme.foreground);
+        polyline::draw_polyline(display, outline.map(|(_, point)| (viewport.position(point), start)), pen)?;
+        let maurer = Maurer { rose: flower.rose, degrees: flower.degrees, steps: 360 };
+        let points = (&maurer as &dyn Curve).sample(1.0).with_end();
+        let corners = points.map(|(i, point)| (viewport.position(point), color.at(i as f32 / 360.0, theme.foreground)));
+        polyline::draw_polyline(display, corners, pen)?;
+
+        self.drawn += 1;
+        /* Flowers may have grown over it */
+        if self.drawn == self.flowers.len() {
+            self.draw_seed(display, settings)?;
+        }
+        Ok(true)
+    }
+
+    /* "seed 1A2B3C4D", the digit being entered on the selection colour */
+    fn draw_seed<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, settings: &Settings) -> Result<(), D::Error> {
+        let theme = settings.theme();
+        /* On a background, so a changed digit overwrites the old one */
+        let style = |color: Rgb565, background: Rgb565| {
+            MonoTextStyleBuilder::new()
+                .font(&FONT_9X15)
+                .text_color(color)
+                .background_color(background)
+                .build()
+        };
+        let mut text: String<24> = String::new();
+        text.push_str(&printable::<16>(&FONT_9X15, Str::Seed.text(settings.language))).ok();
+        text.push(' ').ok();
+        let origin = display.bounding_box().top_left + Point::new(4, 4);
+        let mut position = Text::with_baseline(&text, origin, style(theme.foreground, theme.background), Baseline::Top).draw(display)?;
+
+        let mut digits: String<8> = String::new();
+        write!(digits, "{:08X}", self.seed).ok();
+        for (i, digit) in digits.char_indices() {
+            let style = if self.digit == Some(i as u32) { style(theme.background, theme.selection) } else { style(theme.foreground, theme.background) };
+            position = Text::with_baseline(&digits[i..i + digit.len_utf8()], position, style, Baseline::Top).draw(display)?;
+        }
+        Ok(())
+    }
+}
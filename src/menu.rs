@@ -0,0 +1,159 @@
+/* Main menu: gallery entries, roses saved in the explorer, then "Settings".
+
+   Only `ROWS` entries fit on screen, the rest scroll into view as the pointer reaches them.
+   Triangles on the right show there's more above or below. */
+
+use core::fmt::Write;
+
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{Circle, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, Triangle},
+    text::Text,
+};
+
+use heapless::String;
+use profont::PROFONT_18_POINT;
+
+use crate::gallery::{Artwork, GALLERY};
+use crate::rose_explorer::Favourite;
+use crate::settings::Settings;
+use crate::strings::{printable, Str};
+
+/* As many as fit between the screen centre's 30px above and the bottom edge */
+pub const ROWS: usize = 5;
+
+const ROW_HEIGHT: u32 = 30;
+
+const POINTER_OFFSET: Size = Size::new(15, 10);
+
+#[derive(Clone, Copy)]
+pub enum Entry {
+    Artwork(&'static Artwork),
+    Favourite(Favourite),
+    Settings,
+}
+
+pub struct Menu {
+    origin: Point,
+    selected: usize,
+    /* First entry on screen */
+    first: usize,
+}
+
+impl Menu {
+    pub fn new(origin: Point) -> Self {
+        Menu {
+            origin,
+            selected: 0,
+            first: 0,
+        }
+    }
+
+    pub fn entry(&self, favourites: &[Favourite]) -> Entry {
+        entry(self.selected, favourites)
+    }
+
+    /* Both wrap around, and return whether the entries on screen changed */
+    pub fn up(&mut self, favourites: &[Favourite]) -> bool {
+        let length = length(favourites);
+        self.select((self.selected + length - 1) % length)
+    }
+
+    pub fn down(&mut self, favourites: &[Favourite]) -> bool {
+        self.select((self.selected + 1) % length(favourites))
+    }
+
+    fn select(&mut self, selected: usize) -> bool {
+        self.selected = selected;
+        let first = if selected < self.first {
+            selected
+        } else if selected >= self.first + ROWS {
+            selected + 1 - ROWS
+        } else {
+            self.first
+        };
+        core::mem::replace(&mut self.first, first) != first
+    }
+
+    /* Entries are stacked 30px apart, starting at `origin` */
+    fn tab(&self, row: usize) -> Point {
+        self.origin + Size::new(0, ROW_HEIGHT * row as u32)
+    }
+
+    pub fn draw<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, settings: &Settings, favourites: &[Favourite]) -> Result<(), D::Error> {
+        let theme = settings.theme();
+        let length = length(favourites);
+        let style = MonoTextStyle::new(&PROFONT_18_POINT, theme.foreground);
+        for row in 0..ROWS.min(length - self.first) {
+            let label: String<32> = match entry(self.first + row, favourites) {
+                Entry::Artwork(artwork) => printable(&PROFONT_18_POINT, artwork.title.text(settings.language)),
+                Entry::Favourite(rose) => {
+                    let mut label = printable(&PROFONT_18_POINT, Str::Rose.text(settings.language));
+                    write!(label, " {}/{}", rose.petals, rose.degrees).ok();
+                    label
+                }
+                Entry::Settings => printable(&PROFONT_18_POINT, Str::Settings.text(settings.language)),
+            };
+            Text::new(&label, self.tab(row), style).draw(display)?;
+        }
+
+        /* Pointing up above the first row and down below the last one */
+        let arrow = PrimitiveStyle::with_fill(theme.selection);
+        let x = self.origin.x + 200;
+        if self.first > 0 {
+            let y = self.tab(0).y - 14;
+            Triangle::new(Point::new(x, y), Point::new(x - 5, y + 8), Point::new(x + 5, y + 8))
+                .into_styled(arrow)
+                .draw(display)?;
+        }
+        if self.first + ROWS < length {
+            let y = self.tab(ROWS - 1).y;
+            Triangle::new(Point::new(x, y), Point::new(x - 5, y - 8), Point::new(x + 5, y - 8))
+                .into_styled(arrow)
+                .draw(display)?;
+        }
+
+        self.draw_pointer(display, settings)
+    }
+
+    /* Moves the pointer from `last`, when the entries on screen stayed the same */
+    pub fn move_pointer<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, settings: &Settings, last: usize) -> Result<(), D::Error> {
+        Rectangle::new(self.tab(last - self.first) - Size::new(17, 12), Size::new(15, 15))
+            .into_styled(PrimitiveStyle::with_fill(settings.theme().background))
+            .draw(display)?;
+        self.draw_pointer(display, settings)
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    fn draw_pointer<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, settings: &Settings) -> Result<(), D::Error> {
+        let theme = settings.theme();
+        Circle::new(self.tab(self.selected - self.first) - POINTER_OFFSET, 10)
+            .into_styled(
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(theme.selection)
+                    .stroke_width(1)
+                    .fill_color(theme.selection)
+                    .build(),
+            )
+            .draw(display)
+    }
+}
+
+fn length(favourites: &[Favourite]) -> usize {
+    GALLERY.len() + favourites.len() + 1
+}
+
+fn entry(i: usize, favourites: &[Favourite]) -> Entry {
+    if i < GALLERY.len() {
+        Entry::Artwork(&GALLERY[i])
+    } else if i < GALLERY.len() + favourites.len() {
+        Entry::Favourite(favourites[i - GALLERY.len()])
+    } else {
+        Entry::Settings
+    }
+}
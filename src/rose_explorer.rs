@@ -0,0 +1,164 @@
+/* Maurer rose explorer.
+
+   A Maurer rose joins 361 points of the rose r = sin(n·θ), each d degrees further around than
+   the one before. up/down change n or d, ok picks which of them is changed, or "save", where
+   up/down add the rose to the main menu instead. Saved roses live until the next reset. */
+
+use core::f32::consts::PI;
+use core::fmt::Write;
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_9X15, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::*,
+    text::{Baseline, Text},
+};
+
+use heapless::String;
+
+use crate::controls::Key;
+use crate::curve::{Curve, Maurer, Rose};
+use crate::polyline::{self, Pen};
+use crate::settings::Settings;
+use crate::strings::{printable, Str};
+use crate::viewport::Viewport;
+
+const MAX_PETALS: u32 = 12;
+
+/* Rose and outline, centred in a square frame with a bit of margin */
+const ROSE: Rose = Rose {
+    centre: (0.5, 0.5),
+    radius: 0.46,
+    petals: 1,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Favourite {
+    pub petals: u32,
+    pub degrees: u32,
+}
+
+impl Favourite {
+    /* The Garden's first rose */
+    pub const DEFAULT: Favourite = Favourite { petals: 6, degrees: 71 };
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Petals,
+    Degrees,
+    Save,
+}
+
+pub struct RoseExplorer {
+    rose: Favourite,
+    field: Field,
+    /* Rose saved since `take_favourite` was last called */
+    saved: Option<Favourite>,
+    /* Whether "save" has been used on this rose, it then reads "saved" */
+    saved_this: bool,
+    redraw: bool,
+}
+
+impl RoseExplorer {
+    pub fn new(rose: Favourite) -> Self {
+        RoseExplorer {
+            rose,
+            field: Field::Petals,
+            saved: None,
+            saved_this: false,
+            redraw: true,
+        }
+    }
+
+    pub fn key(&mut self, key: Key) {
+        let step = |value: u32, max: u32, up: bool| if up { value % max + 1 } else { (value + max - 2) % max + 1 };
+        match (key, self.field) {
+            (Key::Ok, field) => {
+                self.field = match field {
+                    Field::Petals => Field::Degrees,
+                    Field::Degrees => Field::Save,
+                    Field::Save => Field::Petals,
+                }
+            }
+            (Key::Up | Key::Down, Field::Petals) => {
+                self.rose.petals = step(self.rose.petals, MAX_PETALS, key == Key::Up);
+                self.saved_this = false;
+            }
+            (Key::Up | Key::Down, Field::Degrees) => {
+                self.rose.degrees = step(self.rose.degrees, 359, key == Key::Up);
+                self.saved_this = false;
+            }
+            (Key::Up | Key::Down, Field::Save) => {
+                if !self.saved_this {
+                    self.saved = Some(self.rose);
+                    self.saved_this = true;
+                }
+            }
+            (Key::Back, _) => return,
+        }
+        self.redraw = true;
+    }
+
+    pub fn take_favourite(&mut self) -> Option<Favourite> {
+        self.saved.take()
+    }
+
+    /* Draws the rose again after any change, returns whether it did */
+    pub fn tick<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, settings: &Settings) -> Result<bool, D::Error> {
+        if !core::mem::take(&mut self.redraw) {
+            return Ok(false);
+        }
+        let theme = settings.theme();
+        display.clear(theme.background)?;
+
+        let viewport = Viewport::fit(display.bounding_box(), 1.0);
+        let rose = Rose { petals: self.rose.petals, ..ROSE };
+        let pen = Pen {
+            width: 1.0,
+            color: theme.accent,
+            background: theme.background,
+        };
+        let outline = (&rose as &dyn Curve).sample(360.0 / PI).with_end();
+        polyline::draw_polyline(display, outline.map(|(_, point)| (viewport.position(point), theme.accent)), pen)?;
+
+        let maurer = Maurer {
+            rose,
+            degrees: self.rose.degrees as f32,
+            steps: 360,
+        };
+        let points = (&maurer as &dyn Curve).sample(1.0).with_end();
+        let pen = Pen {
+            width: settings.line_width.max(1) as f32,
+            color: theme.foreground,
+            ..pen
+        };
+        polyline::draw_polyline(display, points.map(|(_, point)| (viewport.position(point), theme.foreground)), pen)?;
+
+        self.draw_values(display, settings)?;
+        Ok(true)
+    }
+
+    /* n, d and "save" down the left edge, the one being changed marked and in the selection colour */
+    fn draw_values<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, settings: &Settings) -> Result<(), D::Error> {
+        let theme = settings.theme();
+        let origin = display.bounding_box().top_left + Point::new(4, 4);
+        let fields = [Field::Petals, Field::Degrees, Field::Save];
+        for (i, field) in fields.into_iter().enumerate() {
+            let mut text: String<24> = String::new();
+            text.push_str(if field == self.field { ">" } else { " " }).ok();
+            match field {
+                Field::Petals => write!(text, "n={}", self.rose.petals).ok(),
+                Field::Degrees => write!(text, "d={}", self.rose.degrees).ok(),
+                Field::Save => {
+                    let label = if self.saved_this { Str::Saved } else { Str::Save };
+                    text.push_str(&printable::<24>(&FONT_9X15, label.text(settings.language))).ok()
+                }
+            };
+            let color = if field == self.field { theme.selection } else { theme.foreground };
+            Text::with_baseline(&text, origin + Point::new(0, 16 * i as i32), MonoTextStyle::new(&FONT_9X15, color), Baseline::Top)
+                .draw(display)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+/* Where menus and artworks are drawn to.
+
+   Without the `framebuffer` feature this is the display itself and everything shows up while it
+   is being drawn. With it, drawing goes to an off-screen frame and appears on `present()`, only
+   the regions which changed are sent to the display. */
+
+use core::marker::PhantomData;
+
+use embedded_graphics::{
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::Rectangle,
+};
+
+#[cfg(feature = "framebuffer")]
+use crate::framebuffer::Framebuffer;
+
+pub struct Screen<'a, D> {
+    display: D,
+    #[cfg(feature = "framebuffer")]
+    framebuffer: Framebuffer<'a>,
+    _buffer: PhantomData<&'a mut [Rgb565]>,
+}
+
+impl<'a, D: DrawTarget<Color = Rgb565>> Screen<'a, D> {
+    #[cfg(not(feature = "framebuffer"))]
+    pub fn new(display: D) -> Self {
+        Screen {
+            display,
+            _buffer: PhantomData,
+        }
+    }
+
+    /* `buffer` must hold a whole frame of `display` */
+    #[cfg(feature = "framebuffer")]
+    pub fn new(display: D, buffer: &'a mut [Rgb565]) -> Self {
+        let size = display.bounding_box().size;
+        Screen {
+            display,
+            framebuffer: Framebuffer::new(buffer, size),
+            _buffer: PhantomData,
+        }
+    }
+
+    /* Makes everything drawn so far visible */
+    pub fn present(&mut self) -> Result<(), D::Error> {
+        #[cfg(feature = "framebuffer")]
+        self.framebuffer.flush(&mut self.display)?;
+        Ok(())
+    }
+}
+
+impl<D: DrawTarget<Color = Rgb565>> Dimensions for Screen<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.display.bounding_box()
+    }
+}
+
+#[cfg(not(feature = "framebuffer"))]
+impl<D: DrawTarget<Color = Rgb565>> DrawTarget for Screen<'_, D> {
+    type Color = Rgb565;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.display.draw_iter(pixels)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.display.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.display.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.display.clear(color)
+    }
+}
+
+#[cfg(feature = "framebuffer")]
+impl<D: DrawTarget<Color = Rgb565>> DrawTarget for Screen<'_, D> {
+    type Color = Rgb565;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.framebuffer.draw_iter(pixels).map_err(|never| match never {})
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.framebuffer.fill_contiguous(area, colors).map_err(|never| match never {})
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.framebuffer.fill_solid(area, color).map_err(|never| match never {})
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.framebuffer.clear(color).map_err(|never| match never {})
+    }
+}
@@ -0,0 +1,119 @@
+/* Runtime settings and the screen to change them */
+
+use core::fmt::Write;
+
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{Circle, PrimitiveStyle},
+    text::Text,
+};
+
+use heapless::String;
+use profont::PROFONT_18_POINT;
+
+use crate::strings::{printable, Language, Str};
+use crate::theme::{Theme, THEMES};
+
+pub struct Settings {
+    pub language: Language,
+    pub status_bar: bool,
+    /* Draw artworks progressively instead of all at once */
+    pub animation: bool,
+    /* Join curve samples with lines this wide, 0 stamps glyphs as the originals did */
+    pub line_width: u8,
+    /* Index into `THEMES` */
+    pub theme: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Item {
+    Language,
+    StatusBar,
+    Animation,
+    Lines,
+    Theme,
+    Back,
+}
+
+pub const ITEMS: [Item; 6] = [Item::Language, Item::StatusBar, Item::Animation, Item::Lines, Item::Theme, Item::Back];
+
+pub const MAX_LINE_WIDTH: u8 = 3;
+
+/* Tighter than the main menu, so all items fit below the title */
+const ROW_HEIGHT: u32 = 24;
+
+impl Settings {
+    pub fn new(status_bar: bool) -> Self {
+        Settings {
+            language: Language::English,
+            status_bar,
+            animation: false,
+            line_width: 0,
+            theme: 0,
+        }
+    }
+
+    pub fn theme(&self) -> &'static Theme {
+        &THEMES[self.theme]
+    }
+
+    /* Applies `ok` on `item`, returns false when the screen should be left */
+    pub fn activate(&mut self, item: Item) -> bool {
+        match item {
+            Item::Language => self.language = self.language.next(),
+            Item::StatusBar => self.status_bar = !self.status_bar,
+            Item::Animation => self.animation = !self.animation,
+            Item::Lines => self.line_width = (self.line_width + 1) % (MAX_LINE_WIDTH + 1),
+            Item::Theme => self.theme = (self.theme + 1) % THEMES.len(),
+            Item::Back => return false,
+        }
+        true
+    }
+
+    pub fn draw<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, origin: Point, selected: usize) -> Result<(), D::Error> {
+        let theme = self.theme();
+        let style = MonoTextStyle::new(&PROFONT_18_POINT, theme.foreground);
+
+        let title = printable::<32>(&PROFONT_18_POINT, Str::Settings.text(self.language));
+        Text::new(&title, origin - Size::new(20, 40), style).draw(display)?;
+
+        for (i, item) in ITEMS.iter().enumerate() {
+            let text = |value: Str| printable::<24>(&PROFONT_18_POINT, value.text(self.language));
+            let on_off = |on: bool| text(if on { Str::On } else { Str::Off });
+            let (label, value) = match item {
+                Item::Language => (Str::Language, Some(text(self.language.name()))),
+                Item::StatusBar => (Str::StatusBar, Some(on_off(self.status_bar))),
+                Item::Animation => (Str::Animation, Some(on_off(self.animation))),
+                Item::Lines => (Str::Lines, Some(match self.line_width {
+                    0 => on_off(false),
+                    width => {
+                        let mut value = String::new();
+                        write!(value, "{} px", width).ok();
+                        value
+                    }
+                })),
+                Item::Theme => (Str::Theme, Some(text(self.theme().name))),
+                Item::Back => (Str::Back, None),
+            };
+
+            let mut line = printable::<48>(&PROFONT_18_POINT, label.text(self.language));
+            if let Some(value) = value {
+                line.push_str(": ").ok();
+                line.push_str(&value).ok();
+            }
+
+            let position = origin + Size::new(0, ROW_HEIGHT * i as u32);
+            Text::new(&line, position, style).draw(display)?;
+
+            if i == selected {
+                Circle::new(position - Size::new(15, 10), 10)
+                    .into_styled(PrimitiveStyle::with_fill(theme.selection))
+                    .draw(display)?;
+            }
+        }
+
+        Ok(())
+    }
+}
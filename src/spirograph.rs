@@ -0,0 +1,183 @@
+/* Spirograph with a box of rings and wheels to pick from.
+
+   ok picks the ring, the wheel, the pen or the side of the ring the wheel rolls on, up/down
+   change it. The curve is then traced from the start a few hundred segments per tick, so keys
+   still answer while a long one is being drawn, until it closes up. */
+
+use core::fmt::Write;
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_9X15, MonoTextStyleBuilder},
+    pixelcolor::Rgb565,
+    prelude::*,
+    text::{Baseline, Text},
+};
+
+use heapless::String;
+
+use crate::artworks::{self, ASPECT};
+use crate::controls::Key;
+use crate::curve::{Curve, Spirograph};
+use crate::gradient::Gradient;
+use crate::polyline::{self, Pen};
+use crate::settings::Settings;
+use crate::strings::{printable, Str};
+use crate::viewport::Viewport;
+
+/* Teeth of the rings and wheels the toy comes with */
+const RINGS: [u32; 4] = [96, 105, 144, 150];
+const WHEELS: [u32; 16] = [24, 30, 32, 36, 40, 42, 45, 48, 52, 56, 60, 63, 64, 72, 75, 84];
+
+/* Pen holes, in percent of the wheel's radius */
+const MAX_PEN: u32 = 150;
+
+/* Segments drawn per tick, about a frame's worth of lines */
+const SEGMENTS: u32 = 200;
+
+/* Pixels between corners, where the pen is fastest */
+const CORNER_SPACING: f32 = 3.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Ring,
+    Wheel,
+    Pen,
+    Side,
+}
+
+pub struct SpirographExplorer {
+    ring: usize,
+    wheel: usize,
+    pen: u32,
+    outside: bool,
+    field: Field,
+    /* Corners traced so far, out of `corners` */
+    traced: u32,
+    corners: u32,
+    redraw: bool,
+    /* Only the marker moved */
+    values: bool,
+}
+
+impl SpirographExplorer {
+    pub fn new() -> Self {
+        SpirographExplorer {
+            ring: 1,
+            wheel: 11,
+            pen: 80,
+            outside: false,
+            field: Field::Ring,
+            traced: 0,
+            corners: 0,
+            redraw: true,
+            values: false,
+        }
+    }
+
+    pub fn key(&mut self, key: Key) {
+        let step = |value: usize, count: usize, up: bool| if up { (value + 1) % count } else { (value + count - 1) % count };
+        let up = key == Key::Up;
+        match (key, self.field) {
+            (Key::Ok, field) => {
+                self.field = match field {
+                    Field::Ring => Field::Wheel,
+                    Field::Wheel => Field::Pen,
+                    Field::Pen => Field::Side,
+                    Field::Side => Field::Ring,
+                };
+                self.values = true;
+                return;
+            }
+            (Key::Up | Key::Down, Field::Ring) => self.ring = step(self.ring, RINGS.len(), up),
+            (Key::Up | Key::Down, Field::Wheel) => self.wheel = step(self.wheel, WHEELS.len(), up),
+            (Key::Up | Key::Down, Field::Pen) => self.pen = if up { self.pen % MAX_PEN + 10 } else { (self.pen + MAX_PEN - 20) % MAX_PEN + 10 },
+            (Key::Up | Key::Down, Field::Side) => self.outside = !self.outside,
+            (Key::Back, _) => return,
+        }
+        self.redraw = true;
+    }
+
+    fn spirograph(&self) -> Spirograph {
+        let wheel = WHEELS[self.wheel];
+        artworks::spirograph(RINGS[self.ring], wheel, (wheel * self.pen) as f32 / 100.0, self.outside)
+    }
+
+    /* Starts over after any change, otherwise traces the next few segments. Returns whether it
+       drew anything */
+    pub fn tick<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, settings: &Settings) -> Result<bool, D::Error> {
+        let theme = settings.theme();
+        let viewport = Viewport::fit(display.bounding_box(), ASPECT);
+        let spirograph = self.spirograph();
+        let curve: &dyn Curve = &spirograph;
+        let density = spirograph.speed() * spirograph.tooth * viewport.scale() as f32 / CORNER_SPACING;
+
+        if core::mem::take(&mut self.redraw) {
+            display.clear(theme.background)?;
+            self.draw_values(display, settings)?;
+            self.traced = 0;
+            self.corners = curve.sample(density).with_end().len();
+            return Ok(true);
+        }
+        if self.traced >= self.corners {
+            if core::mem::take(&mut self.values) {
+                self.draw_values(display, settings)?;
+                return Ok(true);
+            }
+            return Ok(false);
+        }
+
+        let samples = curve.sample(density);
+        let color = Gradient::Hue { from: 0, to: 360 };
+        let last = self.corners.saturating_sub(1).max(1);
+        let end = (self.traced + SEGMENTS).min(self.corners);
+        /* The segment into the first corner of this tick starts at the last one of the previous */
+        let corners = (self.traced.saturating_sub(1)..end).map(|i| {
+            (viewport.position(samples.point(i)), color.at(i as f32 / last as f32, theme.foreground))
+        });
+        let pen = Pen {
+            width: settings.line_width.max(1) as f32,
+            color: theme.foreground,
+            background: theme.background,
+        };
+        polyline::draw_polyline(display, corners, pen)?;
+        self.traced = end;
+        /* Lines may have crossed them */
+        if self.traced == self.corners {
+            self.draw_values(display, settings)?;
+        }
+        Ok(true)
+    }
+
+    /* Values down the left edge, the one being changed marked and in the selection colour */
+    fn draw_values<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, settings: &Settings) -> Result<(), D::Error> {
+        let theme = settings.theme();
+        let origin = display.bounding_box().top_left + Point::new(4, 4);
+        let fields = [Field::Ring, Field::Wheel, Field::Pen, Field::Side];
+        for (i, field) in fields.into_iter().enumerate() {
+            let mut text: String<24> = String::new();
+            text.push(if field == self.field { '>' } else { ' ' }).ok();
+            let label = match field {
+                Field::Ring => Str::Ring,
+                Field::Wheel => Str::Wheel,
+                Field::Pen => Str::Pen,
+                Field::Side if self.outside => Str::Outside,
+                Field::Side => Str::Inside,
+            };
+            text.push_str(&printable::<16>(&FONT_9X15, label.text(settings.language))).ok();
+            match field {
+                Field::Ring => write!(text, " {}", RINGS[self.ring]).ok(),
+                Field::Wheel => write!(text, " {}", WHEELS[self.wheel]).ok(),
+                Field::Pen => write!(text, " {}%", self.pen).ok(),
+                Field::Side => None,
+            };
+            /* On a background, so the marker is erased where it was */
+            let style = MonoTextStyleBuilder::new()
+                .font(&FONT_9X15)
+                .text_color(if field == self.field { theme.selection } else { theme.foreground })
+                .background_color(theme.background)
+                .build();
+            Text::with_baseline(&text, origin + Point::new(0, 16 * i as i32), style, Baseline::Top).draw(display)?;
+        }
+        Ok(())
+    }
+}
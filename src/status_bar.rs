@@ -0,0 +1,102 @@
+/* Optional strip on top of the screen: artwork name, last render time, chip and CPU clock */
+
+use core::fmt::Write;
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+
+use heapless::String;
+
+use crate::strings::printable;
+use crate::theme::Theme;
+use crate::uptime;
+
+#[cfg(feature = "esp32")]
+pub const CHIP: &str = "esp32";
+#[cfg(feature = "esp32s2")]
+pub const CHIP: &str = "esp32s2";
+#[cfg(feature = "esp32s3")]
+pub const CHIP: &str = "esp32s3";
+#[cfg(feature = "esp32c3")]
+pub const CHIP: &str = "esp32c3";
+
+pub struct StatusBar {
+    pub enabled: bool,
+    artwork: &'static str,
+    render_ms: Option<u64>,
+}
+
+impl StatusBar {
+    pub const HEIGHT: u32 = 14;
+
+    pub fn new(enabled: bool) -> Self {
+        StatusBar {
+            enabled,
+            artwork: "",
+            render_ms: None,
+        }
+    }
+
+    pub fn set_artwork(&mut self, name: &'static str) {
+        self.artwork = name;
+    }
+
+    pub fn set_render_time(&mut self, ms: u64) {
+        self.render_ms = Some(ms);
+    }
+
+    /* Part of `screen` which is left for artworks and menus */
+    pub fn canvas(&self, screen: Rectangle) -> Rectangle {
+        if !self.enabled {
+            return screen;
+        }
+        Rectangle::new(
+            screen.top_left + Size::new(0, Self::HEIGHT),
+            Size::new(screen.size.width, screen.size.height.saturating_sub(Self::HEIGHT)),
+        )
+    }
+
+    pub fn draw<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, theme: &Theme) -> Result<(), D::Error> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let screen = display.bounding_box();
+        let bar = Rectangle::new(screen.top_left, Size::new(screen.size.width, Self::HEIGHT));
+        bar.into_styled(PrimitiveStyle::with_fill(theme.accent))
+            .draw(display)?;
+
+        let text_style = MonoTextStyle::new(&FONT_6X10, theme.background);
+        let middle = bar.top_left.y + Self::HEIGHT as i32 / 2;
+
+        let artwork = printable::<32>(&FONT_6X10, self.artwork);
+        Text::with_baseline(&artwork, Point::new(bar.top_left.x + 3, middle), text_style, Baseline::Middle)
+            .draw(display)?;
+
+        let mut info: String<48> = String::new();
+        match self.render_ms {
+            Some(ms) => write!(info, "{}.{:02} s", ms / 1000, (ms % 1000) / 10),
+            None => write!(info, "-.-- s"),
+        }
+        .ok();
+        write!(info, "  {} @ {} MHz", CHIP, uptime::cpu_mhz()).ok();
+
+        Text::with_text_style(
+            &info,
+            Point::new(bar.top_left.x + screen.size.width as i32 - 3, middle),
+            text_style,
+            TextStyleBuilder::new()
+                .alignment(Alignment::Right)
+                .baseline(Baseline::Middle)
+                .build(),
+        )
+        .draw(display)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+/* Monotonic microsecond clock, used to time renders */
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::hal::clock::Clocks;
+
+#[cfg(any(feature = "esp32s2", feature = "esp32s3", feature = "esp32c3"))]
+use crate::hal::systimer::SystemTimer;
+#[cfg(feature = "esp32")]
+use core::ptr::{addr_of, addr_of_mut};
+#[cfg(feature = "esp32")]
+use crate::hal::{
+    peripherals::TIMG1,
+    timer::{Instance, Timer, Timer0},
+};
+
+static CPU_MHZ: AtomicU32 = AtomicU32::new(0);
+
+#[cfg(feature = "esp32")]
+static mut TIMER: Option<Timer<Timer0<TIMG1>>> = None;
+
+/* Remember the CPU clock so it can be shown on screen */
+pub fn init(clocks: &Clocks) {
+    CPU_MHZ.store(clocks.cpu_clock.to_MHz(), Ordering::Relaxed);
+}
+
+pub fn cpu_mhz() -> u32 {
+    CPU_MHZ.load(Ordering::Relaxed)
+}
+
+#[cfg(any(feature = "esp32s2", feature = "esp32s3", feature = "esp32c3"))]
+pub fn micros() -> u64 {
+    SystemTimer::now() / (SystemTimer::TICKS_PER_SECOND / 1_000_000)
+}
+
+/* ESP32 has no SYSTIMER, so a timer group's 64 bit counter is run at 1 MHz instead. The
+   CPU cycle counter would do too, but it wraps every ~18 s and a render may take longer */
+#[cfg(feature = "esp32")]
+pub fn start(mut timer: Timer<Timer0<TIMG1>>, clocks: &Clocks) {
+    timer.set_divider(clocks.apb_clock.to_MHz() as u16);
+    timer.set_counter_active(true);
+    unsafe { *addr_of_mut!(TIMER) = Some(timer) };
+}
+
+#[cfg(feature = "esp32")]
+pub fn micros() -> u64 {
+    /* Only `start` writes it, before anything is timed */
+    unsafe { (*addr_of!(TIMER)).as_ref().map_or(0, |timer| timer.now()) }
+}
+
+pub fn millis() -> u64 {
+    micros() / 1000
+}
@@ -0,0 +1,189 @@
+/* Rotating wireframe models.
+
+   A frame is drawn every `FRAME_MS`, the model turned a bit further each time. The screen
+   can't be read back, so rather than clearing it the lines of the frame before are drawn again
+   in the background colour, then the new ones. Lines further away are fainter.
+
+   up/down change the model, ok the axis it spins around: x, y, z, or all three at once. */
+
+use core::f32::consts::TAU;
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_9X15, MonoTextStyleBuilder},
+    pixelcolor::Rgb565,
+    prelude::*,
+    text::{Baseline, Text},
+};
+
+use heapless::{String, Vec};
+
+use crate::controls::Key;
+use crate::mesh::{self, Matrix, Mesh};
+use crate::polyline::{self, Pen, Polyline};
+use crate::settings::Settings;
+use crate::strings::{printable, Str};
+use crate::viewport::Viewport;
+
+const FRAME_MS: u64 = 40;
+
+/* Radians per frame, about a turn every 6 s */
+const STEP: f32 = 0.04;
+
+const MODELS: [(&Mesh, Str); 3] = [
+    (&mesh::CUBE, Str::Cube),
+    (&mesh::ICOSAHEDRON, Str::Icosahedron),
+    (&mesh::TORUS, Str::Torus),
+];
+const MAX_VERTICES: usize = 72;
+
+/* Camera 4 units away, and the nearest point of a model 0.38 units from the centre, which
+   keeps it off the labels in the top left corner */
+const DISTANCE: f32 = 4.0;
+const SCALE: f32 = 0.38 * (DISTANCE - 1.0) / DISTANCE;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Axis {
+    X,
+    Y,
+    Z,
+    All,
+}
+
+pub struct Wireframe {
+    model: usize,
+    axis: Axis,
+    /* Turned around x, then y, then z */
+    angles: [f32; 3],
+    /* Where the last frame's lines went, to be erased */
+    drawn: Option<(usize, Vec<(f32, f32), MAX_VERTICES>)>,
+    redraw: bool,
+    labels: bool,
+    next_frame: u64,
+}
+
+impl Wireframe {
+    pub fn new(now: u64) -> Self {
+        Wireframe {
+            model: 0,
+            axis: Axis::All,
+            angles: [0.4, 0.6, 0.0],
+            drawn: None,
+            redraw: true,
+            labels: false,
+            next_frame: now,
+        }
+    }
+
+    pub fn key(&mut self, key: Key) {
+        match key {
+            Key::Up => self.model = (self.model + 1) % MODELS.len(),
+            Key::Down => self.model = (self.model + MODELS.len() - 1) % MODELS.len(),
+            Key::Ok => {
+                self.axis = match self.axis {
+                    Axis::X => Axis::Y,
+                    Axis::Y => Axis::Z,
+                    Axis::Z => Axis::All,
+                    Axis::All => Axis::X,
+                }
+            }
+            Key::Back => return,
+        }
+        self.labels = true;
+    }
+
+    /* Draws a frame when one is due, returns whether it did */
+    pub fn tick<D: DrawTarget<Color = Rgb565>>(&mut self, display: &mut D, settings: &Settings, now: u64) -> Result<bool, D::Error> {
+        let theme = settings.theme();
+        if core::mem::take(&mut self.redraw) {
+            display.clear(theme.background)?;
+            self.drawn = None;
+            self.labels = true;
+        }
+        if now < self.next_frame {
+            return Ok(false);
+        }
+        self.next_frame = (self.next_frame + FRAME_MS).max(now);
+
+        let spin = |angle: &mut f32, rate: f32| *angle = (*angle + STEP * rate) % TAU;
+        match self.axis {
+            Axis::X => spin(&mut self.angles[0], 1.0),
+            Axis::Y => spin(&mut self.angles[1], 1.0),
+            Axis::Z => spin(&mut self.angles[2], 1.0),
+            Axis::All => {
+                spin(&mut self.angles[0], 0.7);
+                spin(&mut self.angles[1], 1.0);
+                spin(&mut self.angles[2], 0.4);
+            }
+        }
+        let rotation = Matrix::rotation(2, self.angles[2])
+            .then(&Matrix::rotation(1, self.angles[1]))
+            .then(&Matrix::rotation(0, self.angles[0]));
+
+        let viewport = Viewport::fit(display.bounding_box(), 1.0);
+        let pen = Pen {
+            width: settings.line_width.max(1) as f32,
+            color: theme.foreground,
+            background: theme.background,
+        };
+        if let Some((model, points)) = &self.drawn {
+            let erase = Pen { color: theme.background, ..pen };
+            for &[from, to] in MODELS[*model].0.edges {
+                polyline::draw_line(display, points[from as usize], points[to as usize], erase)?;
+            }
+        }
+
+        let (mesh, _) = MODELS[self.model];
+        let projected: Vec<((f32, f32), f32), MAX_VERTICES> = mesh
+            .vertices
+            .iter()
+            .map(|&vertex| mesh::project(rotation.apply(vertex), DISTANCE, (0.5, 0.5), SCALE))
+            .map(|(point, depth)| (viewport.position(point), depth))
+            .collect();
+        /* Edges meet at the vertices, one polyline keeps them from cutting into each other */
+        let mut lines = Polyline::new(display.bounding_box());
+        for &[from, to] in mesh.edges {
+            let ((from, near), (to, far)) = (projected[from as usize], projected[to as usize]);
+            /* Full colour at the very front, a third of it at the back */
+            let alpha = 32.0 - (near + far + 2.0) * 16.0 / 3.0;
+            let color = polyline::blend(theme.foreground, theme.background, alpha as u8);
+            lines.draw_line(display, from, to, Pen { color, ..pen })?;
+        }
+        self.drawn = Some((self.model, projected.iter().map(|&(point, _)| point).collect()));
+
+        if core::mem::take(&mut self.labels) {
+            self.draw_labels(display, settings)?;
+        }
+        Ok(true)
+    }
+
+    /* Model and axis in the top left corner */
+    fn draw_labels<D: DrawTarget<Color = Rgb565>>(&self, display: &mut D, settings: &Settings) -> Result<(), D::Error> {
+        let theme = settings.theme();
+        /* On a background, so a longer name before is overwritten */
+        let style = MonoTextStyleBuilder::new()
+            .font(&FONT_9X15)
+            .text_color(theme.foreground)
+            .background_color(theme.background)
+            .build();
+        let origin = display.bounding_box().top_left + Point::new(4, 4);
+        let mut model: String<24> = printable(&FONT_9X15, MODELS[self.model].1.text(settings.language));
+        let mut axis: String<24> = printable(&FONT_9X15, Str::Axis.text(settings.language));
+        let axes = match self.axis {
+            Axis::X => " x",
+            Axis::Y => " y",
+            Axis::Z => " z",
+            Axis::All => " xyz",
+        };
+        axis.push_str(axes).ok();
+        /* As wide as the longest name */
+        while model.len() < 12 {
+            model.push(' ').ok();
+        }
+        while axis.len() < 8 {
+            axis.push(' ').ok();
+        }
+        Text::with_baseline(&model, origin, style, Baseline::Top).draw(display)?;
+        Text::with_baseline(&axis, origin + Point::new(0, 16), style, Baseline::Top).draw(display)?;
+        Ok(())
+    }
+}